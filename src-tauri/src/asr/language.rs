@@ -0,0 +1,56 @@
+//! 识别语言能力表
+//!
+//! 各服务商支持的语言不同，设置界面和切换快捷键只提供表中的组合
+
+use crate::models::config::AsrLanguage;
+use serde::Serialize;
+
+const DOUBAO: &[AsrLanguage] = &[AsrLanguage::Mixed, AsrLanguage::ZhCn, AsrLanguage::EnUs, AsrLanguage::Yue];
+const XUNFEI: &[AsrLanguage] = &[AsrLanguage::Mixed, AsrLanguage::ZhCn, AsrLanguage::EnUs, AsrLanguage::Yue];
+// FunASR 的语言由部署的模型决定，客户端无法切换
+const FUNASR: &[AsrLanguage] = &[AsrLanguage::Mixed];
+
+/// 服务商支持的语言（第一个为默认）
+pub fn supported(provider: &str) -> &'static [AsrLanguage] {
+    match provider {
+        "doubao" => DOUBAO,
+        "xunfei" => XUNFEI,
+        "funasr" => FUNASR,
+        _ => &[],
+    }
+}
+
+/// 服务商不支持该语言时回退为默认语言
+pub fn resolve(provider: &str, language: &AsrLanguage) -> AsrLanguage {
+    let list = supported(provider);
+    if list.contains(language) {
+        return language.clone();
+    }
+    let fallback = list.first().cloned().unwrap_or_default();
+    log::warn!("{} 不支持语言 {:?}，使用 {:?}", provider, language, fallback);
+    fallback
+}
+
+/// 在服务商支持的语言中切换到下一个
+pub fn next(provider: &str, current: &AsrLanguage) -> AsrLanguage {
+    let list = supported(provider);
+    let pos = list.iter().position(|l| l == current);
+    match pos {
+        Some(i) => list[(i + 1) % list.len()].clone(),
+        None => list.first().cloned().unwrap_or_default(),
+    }
+}
+
+#[derive(Serialize)]
+pub struct ProviderLanguages {
+    pub provider: &'static str,
+    pub languages: &'static [AsrLanguage],
+}
+
+/// 全部服务商的能力表（供设置界面使用）
+pub fn capabilities() -> Vec<ProviderLanguages> {
+    ["doubao", "xunfei", "funasr"]
+        .into_iter()
+        .map(|provider| ProviderLanguages { provider, languages: supported(provider) })
+        .collect()
+}
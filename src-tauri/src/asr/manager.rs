@@ -1,6 +1,16 @@
+use super::transcript::{Segment, Transcript, TranscriptEvent};
+use crate::utils::export::Session;
+use crate::utils::output::{self, BufferSink, OutputSink};
 use crate::models::buffer::{AudioBuffer, TextBuffer};
 use crate::models::config::AppConfig;
+use crate::models::validation;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+
+/// 识别结果事件的缓冲条数（订阅者处理不及时会丢弃最早的事件）
+const EVENT_CAPACITY: usize = 256;
 
 /// ASR 提供商枚举（替代 trait object，避免 async-trait 依赖）
 #[derive(Clone)]
@@ -26,6 +36,62 @@ impl AsrProvider {
             AsrProvider::Funasr(p) => p.stop().await,
         }
     }
+
+    pub async fn cancel(&self) {
+        match self {
+            AsrProvider::Xunfei(p) => p.cancel().await,
+            AsrProvider::Doubao(p) => p.cancel().await,
+            AsrProvider::Funasr(p) => p.cancel().await,
+        }
+    }
+
+    /// 立即停止读取音频并发送结束包，继续接收剩余结果（会议轮换连接时音频缓冲区仍在写入）
+    pub fn end_audio(&self) {
+        match self {
+            AsrProvider::Xunfei(p) => p.end_audio(),
+            AsrProvider::Doubao(p) => p.end_audio(),
+            AsrProvider::Funasr(p) => p.end_audio(),
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        match self {
+            AsrProvider::Xunfei(p) => p.is_connected(),
+            AsrProvider::Doubao(p) => p.is_connected(),
+            AsrProvider::Funasr(p) => p.is_connected(),
+        }
+    }
+
+    /// 等待服务端返回最终结果并关闭连接，超时返回 false
+    pub async fn wait_closed(&self, timeout: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + timeout;
+        while self.is_connected() {
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        true
+    }
+}
+
+/// 服务商单次会话的时长上限（会议模式在到达前轮换连接）
+pub fn session_limit(provider: &str) -> Option<Duration> {
+    match provider {
+        "xunfei" => Some(Duration::from_secs(55)),
+        "doubao" => Some(Duration::from_secs(30 * 60)),
+        _ => None,
+    }
+}
+
+/// 服务商的热词是否带权重（FunASR）；其余服务商的热词只提高识别概率，识别后再按热词修正写法
+pub fn weighted_hotwords(provider: &str) -> bool {
+    provider == "funasr"
+}
+
+/// 服务商是否支持说话人分离
+pub fn supports_diarization(provider: &str) -> bool {
+    provider == "doubao"
 }
 
 /// ASR 管理器
@@ -35,6 +101,11 @@ pub struct AsrManager {
     audio_buffer: Arc<AudioBuffer>,
     text_buffer: Arc<TextBuffer>,
     config: Arc<Mutex<AppConfig>>,
+    stop_requested: Arc<AtomicBool>,
+    segments: Arc<Mutex<Vec<Segment>>>,
+    /// 最近一次会话的开始时间（Unix 秒）和服务商
+    session_info: Mutex<(u64, String)>,
+    events: broadcast::Sender<TranscriptEvent>,
 }
 
 impl AsrManager {
@@ -47,35 +118,116 @@ impl AsrManager {
             audio_buffer,
             text_buffer,
             config,
+            stop_requested: Arc::new(AtomicBool::new(false)),
+            segments: Arc::new(Mutex::new(Vec::new())),
+            session_info: Mutex::new((0, String::new())),
+            events: broadcast::channel(EVENT_CAPACITY).0,
         }
     }
 
-    /// 强制创建新的 Provider
+    /// 语音指令是否请求停止听写
+    pub fn stop_requested(&self) -> bool {
+        self.stop_requested.load(Ordering::SeqCst)
+    }
+
+    /// 停止请求已处理（录音已停止）后清除
+    pub fn clear_stop_request(&self) {
+        self.stop_requested.store(false, Ordering::SeqCst);
+    }
+
+    /// 订阅识别结果事件（听写和会议转写）
+    pub fn subscribe(&self) -> broadcast::Receiver<TranscriptEvent> {
+        self.events.subscribe()
+    }
+
+    /// 最近一次听写会话
+    pub fn last_session(&self) -> Session {
+        let (started_at, provider) = self.session_info.lock().unwrap().clone();
+        Session::new(started_at, provider, self.segments.lock().unwrap().clone())
+    }
+
+    /// 强制创建新的 Provider（开始新的会话）
     pub fn create_provider(&self) -> Result<AsrProvider, String> {
-        let asr_config = self.config.lock().unwrap().asr.clone();
+        self.segments.lock().unwrap().clear();
+        let started_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let app = crate::utils::window_ext::foreground_app();
+        let config = self.config.lock().unwrap().effective(app.as_deref());
+        *self.session_info.lock().unwrap() = (started_at, config.asr.provider.clone());
+        let sink = output::build(&config.output, self.text_buffer.clone())?;
+        self.build_provider(config, app.as_deref(), sink, self.segments.clone(), false)
+    }
+
+    /// 创建会议模式的 Provider（输出写入单独的缓冲区，不模拟输入；定稿句子写入 segments）
+    ///
+    /// config 为会议开始时确定的配置（已应用方案），轮换连接时沿用，app 为当时的前台应用
+    pub fn create_meeting_provider(
+        &self,
+        config: AppConfig,
+        app: Option<&str>,
+        text_buffer: Arc<TextBuffer>,
+        segments: Arc<Mutex<Vec<Segment>>>,
+    ) -> Result<AsrProvider, String> {
+        self.build_provider(config, app, Arc::new(BufferSink::new(text_buffer)), segments, true)
+    }
+
+    /// 按会话开始时确定的配置创建 Provider
+    ///
+    /// 会议模式开启说话人分离，不识别语音指令（会议内容不应触发按键或停止听写）
+    fn build_provider(
+        &self,
+        mut config: AppConfig,
+        app: Option<&str>,
+        sink: Arc<dyn OutputSink>,
+        segments: Arc<Mutex<Vec<Segment>>>,
+        meeting: bool,
+    ) -> Result<AsrProvider, String> {
+        validation::check_credentials(&config).map_err(|e| e.to_string())?;
+        let diarization = meeting && config.meeting.diarization;
+        let stop_requested = if meeting {
+            config.voice_commands.enabled = false;
+            Arc::new(AtomicBool::new(false))
+        } else {
+            self.stop_requested.clone()
+        };
+        let (asr_config, vocabulary) = (config.asr.clone(), config.vocabulary.clone());
+        let language = super::language::resolve(&asr_config.provider, &asr_config.language);
+        let transcript = Transcript::new(
+            sink,
+            &config,
+            app,
+            stop_requested,
+            segments,
+            self.events.clone(),
+        );
         
         match asr_config.provider.as_str() {
             "xunfei" => {
                 let p = super::providers::xunfei::XunfeiAsr::new(
                     asr_config.xunfei.clone(),
+                    language,
+                    vocabulary.clone(),
                     self.audio_buffer.clone(),
-                    self.text_buffer.clone(),
+                    transcript,
                 )?;
                 Ok(AsrProvider::Xunfei(p))
             }
             "doubao" => {
                 let p = super::providers::doubao::DoubaoAsr::new(
                     asr_config.doubao.clone(),
+                    language,
+                    diarization,
+                    vocabulary.clone(),
                     self.audio_buffer.clone(),
-                    self.text_buffer.clone(),
+                    transcript,
                 )?;
                 Ok(AsrProvider::Doubao(p))
             }
             "funasr" => {
                 let p = super::providers::funasr::FunasrAsr::new(
                     asr_config.funasr.clone(),
+                    vocabulary.clone(),
                     self.audio_buffer.clone(),
-                    self.text_buffer.clone(),
+                    transcript,
                 )?;
                 Ok(AsrProvider::Funasr(p))
             }
@@ -1,6 +1,8 @@
 //! ASR 模块
 
+pub mod language;
 pub mod manager;
 mod providers;
+pub mod transcript;
 
 pub use manager::{init_asr_manager, AsrManager, AsrProvider};
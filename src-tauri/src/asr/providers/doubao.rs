@@ -1,6 +1,7 @@
-use crate::models::buffer::{AudioBuffer, TextBuffer};
-use crate::models::config::DoubaoConfig;
-use crate::utils::text_diff::compute_diff;
+use crate::asr::transcript::{SharedTranscript, Transcript};
+use crate::models::buffer::AudioBuffer;
+use crate::models::config::{AsrLanguage, DoubaoConfig, HotWord};
+use crate::utils::punctuation::is_cjk;
 use futures::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -15,11 +16,16 @@ const RESOURCE_ID: &str = "volc.seedasr.sauc.duration";
 pub struct DoubaoAsr {
     app_id: String,
     api_key: String,
+    language: AsrLanguage,
+    diarization: bool,
+    hotwords: Vec<HotWord>,
     audio_buffer: Arc<AudioBuffer>,
-    text_buffer: Arc<TextBuffer>,
+    transcript: SharedTranscript,
     ws_sink: Arc<Mutex<Option<tokio::sync::mpsc::Sender<Message>>>>,
     is_connected: Arc<AtomicBool>,
-    text_cache: Arc<Mutex<String>>,
+    /// 停止读取音频（会议轮换连接）
+    audio_ended: Arc<AtomicBool>,
+    tasks: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
 }
 
 #[derive(Serialize)]
@@ -46,22 +52,52 @@ struct ResultItem {
 struct Utterance {
     text: String,
     #[serde(default)] definite: bool,
+    #[serde(default)] start_time: i64,
+    #[serde(default)] end_time: i64,
+    #[serde(default)] additions: serde_json::Value,
+}
+
+impl Utterance {
+    /// 说话人分离结果（additions.speaker，可能为数字或字符串）
+    fn speaker(&self) -> Option<String> {
+        self.additions.get("speaker").map(|v| v.as_str().map(String::from).unwrap_or_else(|| v.to_string()))
+    }
+}
+
+/// 拼接分句文本，两侧都不是中日韩文字时以空格分隔（英文分句不带前后空格）
+fn join_utterances(utterances: &[Utterance]) -> String {
+    let mut text = String::new();
+    for u in utterances {
+        let boundary = text.chars().last().zip(u.text.chars().next());
+        if boundary.is_some_and(|(a, b)| !a.is_whitespace() && !b.is_whitespace() && !is_cjk(a) && !is_cjk(b)) {
+            text.push(' ');
+        }
+        text.push_str(&u.text);
+    }
+    text
 }
 
 impl DoubaoAsr {
     pub fn new(
         config: DoubaoConfig,
+        language: AsrLanguage,
+        diarization: bool,
+        hotwords: Vec<HotWord>,
         audio_buffer: Arc<AudioBuffer>,
-        text_buffer: Arc<TextBuffer>,
+        transcript: Transcript,
     ) -> Result<Self, String> {
         Ok(Self {
             app_id: config.app_id.ok_or("需要 app_id")?,
             api_key: config.api_key.ok_or("需要 api_key")?,
+            language,
+            diarization,
+            hotwords,
             audio_buffer,
-            text_buffer,
+            transcript: SharedTranscript::new(transcript),
             ws_sink: Arc::new(Mutex::new(None)),
             is_connected: Arc::new(AtomicBool::new(false)),
-            text_cache: Arc::new(Mutex::new(String::new())),
+            audio_ended: Arc::new(AtomicBool::new(false)),
+            tasks: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
@@ -87,16 +123,40 @@ impl DoubaoAsr {
     }
 
     fn build_init_packet(&self, reqid: &str) -> Vec<u8> {
+        let mut request = serde_json::json!({
+            "model_name": "bigmodel", "reqid": reqid, "sequence": 1,
+            "show_utterances": true, "enable_punc": true,
+            "enable_vad": true, "end_window_size": 800
+        });
+        if self.diarization {
+            request["enable_speaker_info"] = true.into();
+        }
+        // 热词通过 corpus.context 传递；context 没有权重字段，按权重从高到低排列（超出数量上限时保留权重高的）
+        if !self.hotwords.is_empty() {
+            let mut hotwords: Vec<&HotWord> = self.hotwords.iter().collect();
+            hotwords.sort_by_key(|w| std::cmp::Reverse(w.weight.unwrap_or(0)));
+            let words: Vec<_> = hotwords.iter().map(|w| serde_json::json!({"word": w.word})).collect();
+            request["corpus"] = serde_json::json!({
+                "context": serde_json::json!({"hotwords": words}).to_string()
+            });
+        }
+        let mut audio = serde_json::json!({
+            "format": "pcm", "rate": 16000, "bits": 16, "channel": 1, "codec": "raw"
+        });
+        // 未指定语言时服务端自动识别中英混合
+        let language = match self.language {
+            AsrLanguage::Mixed => None,
+            AsrLanguage::ZhCn => Some("zh-CN"),
+            AsrLanguage::EnUs => Some("en-US"),
+            AsrLanguage::Yue => Some("yue-CN"),
+        };
+        if let Some(language) = language {
+            audio["language"] = language.into();
+        }
         let payload = RequestPayload {
             user: serde_json::json!({"uid": "user"}),
-            audio: serde_json::json!({
-                "format": "pcm", "rate": 16000, "bits": 16, "channel": 1, "codec": "raw"
-            }),
-            request: serde_json::json!({
-                "model_name": "bigmodel", "reqid": reqid, "sequence": 1,
-                "show_utterances": true, "enable_punc": true,
-                "enable_vad": true, "end_window_size": 800
-            }),
+            audio,
+            request,
         };
         let bytes = serde_json::to_vec(&payload).unwrap();
         let header = [0x11, 0x10, 0x10, 0x00];
@@ -147,11 +207,12 @@ impl DoubaoAsr {
     }
 
     async fn start_listening(&self, stream: futures::stream::SplitStream<tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>>) {
-        let text_buf = self.text_buffer.clone();
         let connected = self.is_connected.clone();
-        let text_cache = self.text_cache.clone();
+        let transcript = self.transcript.clone();
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
+            // 已定稿的分句数（服务端每次返回全部分句，定稿的分句不会再变）
+            let mut definite_count = 0;
             futures::pin_mut!(stream);
             while let Some(msg_result) = stream.next().await {
                 match msg_result {
@@ -164,25 +225,32 @@ impl DoubaoAsr {
                             }
 
                             if let Some(result) = &resp.result {
-                                let mut cache = text_cache.lock().await;
-                                let new_text = &result.text;
-                                
-                                if *new_text != *cache {
-                                    let (backspace, addition) = compute_diff(&cache, new_text);
-                                    // 先发送退格键（每个都是独立的队列元素）
-                                    if backspace > 0 {
-                                        text_buf.push_backspaces(backspace);
-                                    }
-                                    // 再发送新增文本（每个字符都是独立的队列元素）
-                                    if !addition.is_empty() {
-                                        text_buf.push_text(&addition);
+                                if result.utterances.is_empty() {
+                                    // 无分句信息时按整段处理，会话结束时定稿
+                                    if seq < 0 {
+                                        transcript.commit(&result.text).await;
+                                    } else {
+                                        transcript.update(&result.text).await;
                                     }
-                                    *cache = new_text.clone();
+                                    continue;
                                 }
-                                
-                                // 会话结束，清空缓存
+
+                                // 依次定稿新的 definite 分句，只对末尾未定稿的部分做差量修正
+                                for u in result.utterances.iter().skip(definite_count) {
+                                    if !u.definite { break; }
+                                    let timing = (u.start_time.max(0) as u64, u.end_time.max(0) as u64);
+                                    transcript.commit_timed(&u.text, Some(timing), u.speaker()).await;
+                                    definite_count += 1;
+                                }
+                                let rest_utterances = &result.utterances[definite_count.min(result.utterances.len())..];
+                                let rest = join_utterances(rest_utterances);
                                 if seq < 0 {
-                                    cache.clear();
+                                    let timing = rest_utterances.first().zip(rest_utterances.last())
+                                        .map(|(a, b)| (a.start_time.max(0) as u64, b.end_time.max(0) as u64));
+                                    let speaker = rest_utterances.last().and_then(|u| u.speaker());
+                                    transcript.commit_timed(&rest, timing, speaker).await;
+                                } else {
+                                    transcript.update(&rest).await;
                                 }
                             }
                         }
@@ -194,6 +262,7 @@ impl DoubaoAsr {
             }
             connected.store(false, Ordering::SeqCst);
         });
+        self.tasks.lock().await.push(task);
     }
 
     pub async fn start(&self) -> Result<(), String> {
@@ -213,12 +282,12 @@ impl DoubaoAsr {
         *self.ws_sink.lock().await = Some(tx);
 
         let this = self.clone();
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             let mut buf = vec![0i16; 3200];
             let mut seq: i32 = 2;
 
             loop {
-                if !this.is_connected.load(Ordering::SeqCst) { break; }
+                if !this.is_connected.load(Ordering::SeqCst) || this.audio_ended.load(Ordering::SeqCst) { break; }
                 if let Ok(msg) = rx.try_recv() { let _ = sink.send(msg).await; }
 
                 let n = this.audio_buffer.read(&mut buf);
@@ -243,13 +312,52 @@ impl DoubaoAsr {
                 let _ = sink.close().await;
             }
         });
+        self.tasks.lock().await.push(task);
 
         Ok(())
     }
 
+    /// 停止：不断开连接，发送任务发完剩余音频后发送结束包，监听任务收到最终结果后结束
     pub async fn stop(&self) {
+        *self.ws_sink.lock().await = None;
+    }
+
+    /// 立即停止读取音频并发送结束包，继续接收剩余结果
+    pub fn end_audio(&self) {
+        self.audio_ended.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.is_connected.load(Ordering::SeqCst)
+    }
+
+    /// 取消：中止收发任务，不再等待服务端返回结果
+    pub async fn cancel(&self) {
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
         self.is_connected.store(false, Ordering::SeqCst);
         *self.ws_sink.lock().await = None;
-        self.text_cache.lock().await.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterances(texts: &[&str]) -> Vec<Utterance> {
+        texts
+            .iter()
+            .map(|t| Utterance { text: t.to_string(), definite: false, start_time: 0, end_time: 0, additions: serde_json::Value::Null })
+            .collect()
+    }
+
+    #[test]
+    fn join_utterances_spaces_latin_text() {
+        assert_eq!(join_utterances(&utterances(&["Hello world.", "How are you?"])), "Hello world. How are you?");
+        assert_eq!(join_utterances(&utterances(&["你好。", "今天天气不错。"])), "你好。今天天气不错。");
+        assert_eq!(join_utterances(&utterances(&["OK.", "好的"])), "OK.好的");
+        assert_eq!(join_utterances(&utterances(&["Hi ", "there"])), "Hi there");
+        assert_eq!(join_utterances(&[]), "");
     }
 }
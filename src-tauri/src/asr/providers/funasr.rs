@@ -1,5 +1,6 @@
-use crate::models::buffer::{AudioBuffer, TextBuffer};
-use crate::models::config::FunasrConfig;
+use crate::asr::transcript::{SharedTranscript, Transcript};
+use crate::models::buffer::AudioBuffer;
+use crate::models::config::{FunasrConfig, FunasrMode, HotWord};
 use futures::{SinkExt, StreamExt};
 use serde::Deserialize;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -8,23 +9,38 @@ use tokio::sync::Mutex;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
 const CHUNK_SAMPLES_100MS: usize = 1600; // 16kHz * 0.1s
-const FINISH_CMD: &str = "{\"cmd\":\"finish\"}";
+/// 结束发送音频（官方 WebSocket 协议）
+const END_MESSAGE: &str = "{\"is_speaking\":false}";
+/// 结束发送后等待最后一段离线结果的最长时间，超时后关闭连接
+const FINAL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
 const CHUNK_INTERVAL_100MS: std::time::Duration = std::time::Duration::from_millis(100);
+const DEFAULT_HOTWORD_WEIGHT: u8 = 20;
 
 #[derive(Clone)]
 pub struct FunasrAsr {
     host: String,
     port: u16,
+    mode: FunasrMode,
+    chunk_size: [u32; 3],
+    type_partial: bool,
+    hotwords: Vec<HotWord>,
     audio_buffer: Arc<AudioBuffer>,
-    text_buffer: Arc<TextBuffer>,
+    transcript: SharedTranscript,
     ws_sink: Arc<Mutex<Option<tokio::sync::mpsc::Sender<Message>>>>,
     is_connected: Arc<AtomicBool>,
+    /// 停止读取音频（会议轮换连接）
+    audio_ended: Arc<AtomicBool>,
+    /// 已发送结束消息，收到离线结果后会话结束
+    end_sent: Arc<AtomicBool>,
+    tasks: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
 }
 
+/// 识别结果（官方协议）：mode 为 online / 2pass-online 时 text 为实时结果的增量，
+/// 为 offline / 2pass-offline 时为一段的离线识别结果
 #[derive(Deserialize)]
-struct FunasrEvent {
+struct FunasrResult {
     #[serde(default)]
-    r#type: String,
+    mode: String,
     #[serde(default)]
     text: String,
 }
@@ -36,8 +52,9 @@ impl FunasrAsr {
 
     pub fn new(
         config: FunasrConfig,
+        hotwords: Vec<HotWord>,
         audio_buffer: Arc<AudioBuffer>,
-        text_buffer: Arc<TextBuffer>,
+        transcript: Transcript,
     ) -> Result<Self, String> {
         let host = config.host.trim().to_string();
         if host.is_empty() {
@@ -50,10 +67,17 @@ impl FunasrAsr {
         Ok(Self {
             host,
             port: config.port,
+            mode: config.mode,
+            chunk_size: config.chunk_size,
+            type_partial: config.type_partial,
+            hotwords,
             audio_buffer,
-            text_buffer,
+            transcript: SharedTranscript::new(transcript),
             ws_sink: Arc::new(Mutex::new(None)),
             is_connected: Arc::new(AtomicBool::new(false)),
+            audio_ended: Arc::new(AtomicBool::new(false)),
+            end_sent: Arc::new(AtomicBool::new(false)),
+            tasks: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
@@ -61,6 +85,30 @@ impl FunasrAsr {
         format!("ws://{}:{}/ws/asr", self.host, self.port)
     }
 
+    /// 会话开始时发送的配置消息（模式、分块、热词）
+    fn build_config_message(&self) -> String {
+        let mode = match self.mode {
+            FunasrMode::TwoPass => "2pass",
+            FunasrMode::Online => "online",
+            FunasrMode::Offline => "offline",
+        };
+        let mut config = serde_json::json!({
+            "mode": mode,
+            "chunk_size": self.chunk_size,
+            "chunk_interval": 10,
+            "wav_name": "voicekit",
+            "is_speaking": true,
+        });
+        // 热词格式: {"词": 权重} 的 JSON 字符串
+        if !self.hotwords.is_empty() {
+            let words: serde_json::Map<String, serde_json::Value> = self.hotwords.iter()
+                .map(|w| (w.word.clone(), w.weight.unwrap_or(DEFAULT_HOTWORD_WEIGHT).into()))
+                .collect();
+            config["hotwords"] = serde_json::Value::Object(words).to_string().into();
+        }
+        config.to_string()
+    }
+
     fn start_listening(
         &self,
         mut stream: futures::stream::SplitStream<
@@ -68,28 +116,40 @@ impl FunasrAsr {
                 tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
             >,
         >,
-    ) {
-        let text_buffer = self.text_buffer.clone();
+    ) -> tokio::task::JoinHandle<()> {
+        let transcript = self.transcript.clone();
         let is_connected = self.is_connected.clone();
+        let end_sent = self.end_sent.clone();
+        let type_partial = self.type_partial;
 
         tokio::spawn(async move {
+            // 当前段已收到的实时结果
+            let mut online = String::new();
             while let Some(msg_result) = stream.next().await {
                 match msg_result {
                     Ok(Message::Text(text)) => {
-                        let Ok(event) = serde_json::from_str::<FunasrEvent>(&text) else {
+                        let Ok(result) = serde_json::from_str::<FunasrResult>(&text) else {
                             continue;
                         };
 
-                        if event.r#type == "final" {
-                            let final_text = event.text.trim();
-                            if !final_text.is_empty() {
-                                text_buffer.push_text(final_text);
+                        match result.mode.as_str() {
+                            // 实时结果始终推送，按配置决定是否实时输入
+                            "online" | "2pass-online" => {
+                                online.push_str(&result.text);
+                                if type_partial {
+                                    transcript.update(online.trim()).await;
+                                } else {
+                                    transcript.preview(online.trim()).await;
+                                }
+                            }
+                            // 离线结果替换该段的实时结果
+                            _ => {
+                                online.clear();
+                                transcript.commit(result.text.trim()).await;
+                                if end_sent.load(Ordering::SeqCst) {
+                                    break;
+                                }
                             }
-                            continue;
-                        }
-
-                        if event.r#type == "done" || event.r#type == "error" {
-                            break;
                         }
                     }
                     Ok(Message::Close(_)) => break,
@@ -98,8 +158,12 @@ impl FunasrAsr {
                 }
             }
 
+            // online 模式没有离线结果，会话结束时以累积的实时结果定稿
+            if !online.trim().is_empty() {
+                transcript.commit(online.trim()).await;
+            }
             is_connected.store(false, Ordering::SeqCst);
-        });
+        })
     }
 
     pub async fn start(&self) -> Result<(), String> {
@@ -109,14 +173,19 @@ impl FunasrAsr {
             .map_err(|e| format!("FunASR 连接失败: {}", e))?;
 
         let (mut ws_sink, ws_stream) = ws_stream.split();
+        ws_sink
+            .send(Message::Text(self.build_config_message()))
+            .await
+            .map_err(|e| format!("FunASR 发送配置失败: {}", e))?;
         self.is_connected.store(true, Ordering::SeqCst);
-        self.start_listening(ws_stream);
+        let listen_task = self.start_listening(ws_stream);
+        self.tasks.lock().await.push(listen_task);
 
         let (tx, mut rx) = tokio::sync::mpsc::channel::<Message>(16);
         *self.ws_sink.lock().await = Some(tx);
 
         let this = self.clone();
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             let mut read_buf = vec![0i16; CHUNK_SAMPLES_100MS];
             let mut pending_samples: Vec<i16> = Vec::with_capacity(CHUNK_SAMPLES_100MS * 2);
 
@@ -124,6 +193,12 @@ impl FunasrAsr {
                 if !this.is_connected.load(Ordering::SeqCst) {
                     break;
                 }
+                if this.audio_ended.load(Ordering::SeqCst) {
+                    if !pending_samples.is_empty() {
+                        let _ = ws_sink.send(Message::Binary(Self::pcm16le_bytes(&pending_samples))).await;
+                    }
+                    break;
+                }
 
                 if let Ok(msg) = rx.try_recv() {
                     if ws_sink.send(msg).await.is_err() {
@@ -167,22 +242,41 @@ impl FunasrAsr {
             }
 
             if this.is_connected.load(Ordering::SeqCst) {
-                let _ = ws_sink
-                    .send(Message::Text(FINISH_CMD.to_string()))
-                    .await;
+                this.end_sent.store(true, Ordering::SeqCst);
+                let _ = ws_sink.send(Message::Text(END_MESSAGE.to_string())).await;
+                // 服务端不会主动关闭连接：等待最后一段离线结果（监听任务收到后结束），超时后关闭
+                let deadline = tokio::time::Instant::now() + FINAL_TIMEOUT;
+                while this.is_connected.load(Ordering::SeqCst) && tokio::time::Instant::now() < deadline {
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                }
                 let _ = ws_sink.close().await;
             }
         });
+        self.tasks.lock().await.push(task);
 
         Ok(())
     }
 
+    /// 停止：不断开连接，发送任务发完剩余音频后发送结束消息，监听任务收到最终结果后结束
     pub async fn stop(&self) {
-        let sink = self.ws_sink.lock().await.take();
-        if let Some(sink) = sink {
-            let _ = sink.send(Message::Text(FINISH_CMD.to_string())).await;
-        }
+        *self.ws_sink.lock().await = None;
+    }
+
+    /// 立即停止读取音频并发送结束包，继续接收剩余结果
+    pub fn end_audio(&self) {
+        self.audio_ended.store(true, Ordering::SeqCst);
+    }
 
+    pub fn is_connected(&self) -> bool {
+        self.is_connected.load(Ordering::SeqCst)
+    }
+
+    /// 取消：中止收发任务，不再等待服务端返回结果
+    pub async fn cancel(&self) {
+        for task in self.tasks.lock().await.drain(..) {
+            task.abort();
+        }
+        self.ws_sink.lock().await.take();
         self.is_connected.store(false, Ordering::SeqCst);
     }
 }
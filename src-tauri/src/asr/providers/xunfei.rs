@@ -1,10 +1,12 @@
-use crate::models::buffer::{AudioBuffer, TextBuffer};
-use crate::models::config::XunfeiConfig;
+use crate::asr::transcript::{SharedTranscript, Transcript};
+use crate::models::buffer::AudioBuffer;
+use crate::models::config::{AsrLanguage, HotWord, XunfeiConfig};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use futures::{SinkExt, StreamExt};
 use hmac::{Hmac, Mac};
 use serde::{Deserialize, Serialize};
 use sha2::Sha256;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -23,12 +25,18 @@ pub struct XunfeiAsr {
     app_id: String,
     api_key: String,
     api_secret: String,
+    language: AsrLanguage,
+    hotwords: Vec<HotWord>,
     audio_buffer: Arc<AudioBuffer>,
-    text_buffer: Arc<TextBuffer>,
+    transcript: SharedTranscript,
     ws_sink: Arc<Mutex<Option<tokio::sync::mpsc::Sender<Message>>>>,
     status: Arc<AtomicU8>,
     is_connected: Arc<AtomicBool>,
-    text_cache: Arc<Mutex<String>>,
+    /// 停止读取音频（会议轮换连接）
+    audio_ended: Arc<AtomicBool>,
+    /// 动态修正模式下按序号保存的各段结果
+    sentences: Arc<Mutex<Segments>>,
+    tasks: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
 }
 
 // 请求/响应数据结构
@@ -61,8 +69,15 @@ struct ResponsePayload { #[serde(default)] result: Option<ResultData> }
 #[derive(Deserialize)]
 struct ResultData { text: String }
 
+/// 识别结果（dwa=wpgs 时 pgs 为 apd 追加 / rpl 替换，rg 为被替换的序号范围，ls 表示最后一段）
 #[derive(Deserialize)]
-struct ResultText { ws: Vec<WordSlice> }
+struct ResultText {
+    #[serde(default)] sn: u32,
+    #[serde(default)] ls: bool,
+    #[serde(default)] pgs: Option<String>,
+    #[serde(default)] rg: Option<[u32; 2]>,
+    ws: Vec<WordSlice>,
+}
 
 #[derive(Deserialize)]
 struct WordSlice { cw: Vec<WordCell> }
@@ -70,22 +85,102 @@ struct WordSlice { cw: Vec<WordCell> }
 #[derive(Deserialize)]
 struct WordCell { w: String }
 
+/// 句末标点，未定稿的段以此结尾时提交到此为止的内容
+const SENTENCE_ENDS: &[char] = &['。', '！', '？', '!', '?', '…'];
+
+/// 是否以句末标点结尾（数字后的 . 可能是小数点，不算句末）
+fn ends_sentence(text: &str) -> bool {
+    let mut chars = text.trim_end().chars().rev();
+    match chars.next() {
+        Some('.') => chars.next().is_some_and(|c| !c.is_ascii_digit()),
+        Some(c) => SENTENCE_ENDS.contains(&c),
+        None => false,
+    }
+}
+
+/// 动态修正的各段结果（按序号）
+#[derive(Default)]
+struct Segments {
+    /// 已提交的段，rpl 仍可能回溯到这些段
+    committed: BTreeMap<u32, String>,
+    /// 未提交的段
+    pending: BTreeMap<u32, String>,
+}
+
+impl Segments {
+    /// 合入一段结果，返回（新定稿的文本, 未定稿的文本）
+    ///
+    /// 只在 ls（最后一段）或句末标点处定稿。rpl 的范围回溯到已提交的段时，新结果中对应已提交
+    /// 文本的部分不再输出（已提交的内容无法撤回），其余部分作为该段未定稿的文本
+    fn merge(&mut self, result: ResultText) -> (String, String) {
+        let mut text: String = result.ws.iter().flat_map(|s| &s.cw).map(|c| c.w.as_str()).collect();
+        if result.pgs.as_deref() == Some("rpl") {
+            if let Some([start, end]) = result.rg {
+                let replaced: Vec<u32> = self.committed.range(start..=end).map(|(sn, _)| *sn).collect();
+                let committed: String = replaced.iter().filter_map(|sn| self.committed.remove(sn)).collect();
+                self.pending.retain(|sn, _| *sn < start || *sn > end);
+
+                let count = committed.chars().count();
+                if count > 0 {
+                    let split = text.char_indices().nth(count).map_or(text.len(), |(i, _)| i);
+                    if !text.starts_with(&committed) {
+                        log::debug!("讯飞 ASR 修正了已提交的文本: {} -> {}", committed, &text[..split]);
+                    }
+                    self.committed.insert(result.sn, committed);
+                    text = text[split..].to_string();
+                }
+            }
+        }
+        self.pending.insert(result.sn, text);
+
+        let bound = if result.ls {
+            self.pending.keys().next_back().copied()
+        } else {
+            self.pending.iter().rev().find(|(_, t)| ends_sentence(t)).map(|(sn, _)| *sn)
+        };
+        let mut done = String::new();
+        if let Some(bound) = bound {
+            let rest = self.pending.split_off(&bound.saturating_add(1));
+            for (sn, text) in std::mem::replace(&mut self.pending, rest) {
+                done.push_str(&text);
+                self.committed.entry(sn).or_default().push_str(&text);
+            }
+        }
+        if result.ls {
+            self.committed.clear();
+        }
+        (done, self.pending.values().map(String::as_str).collect())
+    }
+
+    /// 取出全部未定稿的文本
+    fn take_pending(&mut self) -> String {
+        self.committed.clear();
+        std::mem::take(&mut self.pending).into_values().collect()
+    }
+}
+
 impl XunfeiAsr {
     pub fn new(
         config: XunfeiConfig,
+        language: AsrLanguage,
+        hotwords: Vec<HotWord>,
         audio_buffer: Arc<AudioBuffer>,
-        text_buffer: Arc<TextBuffer>,
+        transcript: Transcript,
     ) -> Result<Self, String> {
         Ok(Self {
             app_id: config.app_id.clone().ok_or("讯飞 ASR 需要 app_id")?,
             api_key: config.api_key.clone().ok_or("讯飞 ASR 需要 api_key")?,
             api_secret: config.api_secret.clone().ok_or("讯飞 ASR 需要 api_secret")?,
+            language,
+            hotwords,
             audio_buffer,
-            text_buffer,
+            transcript: SharedTranscript::new(transcript),
             ws_sink: Arc::new(Mutex::new(None)),
             status: Arc::new(AtomicU8::new(0)),
             is_connected: Arc::new(AtomicBool::new(false)),
-            text_cache: Arc::new(Mutex::new(String::new())),
+            audio_ended: Arc::new(AtomicBool::new(false)),
+            sentences: Arc::new(Mutex::new(Segments::default())),
+            tasks: Arc::new(Mutex::new(Vec::new())),
         })
     }
 
@@ -105,15 +200,31 @@ impl XunfeiAsr {
             XFYUN_WS_URL, urlencoding::encode(&authorization), urlencoding::encode(&date), XFYUN_HOST)
     }
 
+    /// 识别语言对应的 language / accent 参数
+    fn language_params(&self) -> (&'static str, &'static str) {
+        match self.language {
+            AsrLanguage::Mixed => ("mul_cn", "mandarin"),
+            AsrLanguage::ZhCn => ("zh_cn", "mandarin"),
+            AsrLanguage::EnUs => ("en_us", "mandarin"),
+            AsrLanguage::Yue => ("zh_cn", "cantonese"),
+        }
+    }
+
     /// 构建帧数据 (status: 0=首帧, 1=中间帧, 2=结束帧)
     fn build_frame(&self, status: u8, audio_b64: String) -> String {
         let parameter = if status == 0 {
-            Some(serde_json::json!({
-                "iat": {
-                    "domain": "slm", "language": "mul_cn", "accent": "mandarin",
-                    "result": { "encoding": "utf8", "compress": "raw", "format": "json" }
-                }
-            }))
+            let (language, accent) = self.language_params();
+            // dwa=wpgs 开启动态修正，服务端返回需替换的结果范围
+            let mut iat = serde_json::json!({
+                "domain": "slm", "language": language, "accent": accent, "dwa": "wpgs",
+                "result": { "encoding": "utf8", "compress": "raw", "format": "json" }
+            });
+            // 动态热词: "utf-8;词1|词2"
+            if !self.hotwords.is_empty() {
+                let words: Vec<&str> = self.hotwords.iter().map(|w| w.word.as_str()).collect();
+                iat["dhw"] = format!("utf-8;{}", words.join("|")).into();
+            }
+            Some(serde_json::json!({ "iat": iat }))
         } else { None };
         serde_json::to_string(&RequestData {
             header: RequestHeader { status, app_id: self.app_id.clone() },
@@ -124,22 +235,20 @@ impl XunfeiAsr {
     }
 
     /// 解析识别结果
-    fn parse_result(text: &str) -> Option<String> {
+    fn parse_result(text: &str) -> Option<ResultText> {
         let decoded = BASE64.decode(text).ok()?;
         let json_str = String::from_utf8(decoded).ok()?;
-        let result: ResultText = serde_json::from_str(&json_str).ok()?;
-        let text: String = result.ws.iter().flat_map(|s| &s.cw).map(|c| c.w.as_str()).collect();
-        if text.is_empty() { None } else { Some(text) }
+        serde_json::from_str(&json_str).ok()
     }
 
     /// 启动 WebSocket 监听
     async fn start_listening(&self, mut ws_stream: futures::stream::SplitStream<WsStream>) {
-        let text_buffer = self.text_buffer.clone();
+        let transcript = self.transcript.clone();
         let status = self.status.clone();
         let is_connected = self.is_connected.clone();
-        let text_cache = self.text_cache.clone();
+        let sentences = self.sentences.clone();
 
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             while let Some(Ok(msg)) = ws_stream.next().await {
                 match msg {
                     Message::Text(text) => {
@@ -151,21 +260,18 @@ impl XunfeiAsr {
                                 data.header.code, data.header.message);
                             is_connected.store(false, Ordering::SeqCst); break;
                         }
-                        if let Some(text) = data.payload.and_then(|p| p.result)
+                        if let Some(result) = data.payload.and_then(|p| p.result)
                             .and_then(|r| Self::parse_result(&r.text)) {
-                            log::info!("讯飞原始文本: {}", text);
-                            let cache = text_cache.clone();
-                            let buf = text_buffer.clone();
-                            let mut c = cache.lock().await;
-                            let to_send = if text.starts_with(&*c) && !c.is_empty() {
-                                text[c.len()..].to_string()
-                            } else { text.clone() };
-                            if !to_send.is_empty() { buf.push_text(&to_send); }
-                            *c = text;
+                            log::info!("讯飞原始结果: sn={}, pgs={:?}, rg={:?}", result.sn, result.pgs, result.rg);
+                            let (done, pending) = sentences.lock().await.merge(result);
+                            // 定稿的句子逐句提交（语音指令按句匹配），未定稿部分的差异由 compute_diff 转为退格 + 新增
+                            if !done.is_empty() {
+                                transcript.commit(&done).await;
+                            }
+                            transcript.update(&pending).await;
                         }
                         if data.header.status == 2 {
                             log::info!("讯飞 ASR 会话结束");
-                            text_cache.lock().await.clear();  // 重置缓存
                             is_connected.store(false, Ordering::SeqCst); break;
                         }
                     }
@@ -178,9 +284,15 @@ impl XunfeiAsr {
                     _ => {}
                 }
             }
+            // 会话结束或连接断开时，未定稿的段按最终结果提交
+            let pending = sentences.lock().await.take_pending();
+            if !pending.is_empty() {
+                transcript.commit(&pending).await;
+            }
             status.store(0, Ordering::SeqCst);
             log::info!("讯飞 ASR 监听任务结束");
         });
+        self.tasks.lock().await.push(task);
     }
 
     pub async fn start(&self) -> Result<(), String> {
@@ -197,11 +309,11 @@ impl XunfeiAsr {
         *self.ws_sink.lock().await = Some(tx);
 
         let self_clone = self.clone();
-        tokio::spawn(async move {
+        let task = tokio::spawn(async move {
             let mut buf = vec![0i16; 480];  // 30ms @ 16kHz
             let (mut frames, mut total_samples) = (0, 0);
             loop {
-                if !self_clone.is_connected.load(Ordering::SeqCst) { break; }
+                if !self_clone.is_connected.load(Ordering::SeqCst) || self_clone.audio_ended.load(Ordering::SeqCst) { break; }
                 if let Ok(msg) = rx.try_recv() { let _ = ws_sink.send(msg).await; }
                 let count = self_clone.audio_buffer.read(&mut buf);
                 if count == 0 { if self_clone.audio_buffer.is_finished() { break; } continue; }
@@ -226,16 +338,86 @@ impl XunfeiAsr {
                 let _ = ws_sink.close().await;
             }
         });
+        self.tasks.lock().await.push(task);
         Ok(())
     }
 
+    /// 停止：不断开连接，发送任务发完剩余音频后发送结束帧，监听任务收到最终结果后结束
     pub async fn stop(&self) {
         log::info!("讯飞 ASR 停止");
+        // 不清理 sentences：停止后服务端仍会返回剩余结果，由监听任务在会话结束时提交
+        *self.ws_sink.lock().await = None;
+    }
+
+    /// 立即停止读取音频并发送结束包，继续接收剩余结果
+    pub fn end_audio(&self) {
+        self.audio_ended.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.is_connected.load(Ordering::SeqCst)
+    }
+
+    /// 取消：中止收发任务，不再等待服务端返回结果
+    pub async fn cancel(&self) {
+        log::info!("讯飞 ASR 取消");
+        for task in self.tasks.lock().await.drain(..) { task.abort(); }
         self.is_connected.store(false, Ordering::SeqCst);
-        if let Some(sink) = self.ws_sink.lock().await.as_ref() {
-            let _ = sink.send(Message::Text(self.build_frame(2, String::new()))).await;
-        }
         *self.ws_sink.lock().await = None;
-        self.text_cache.lock().await.clear();  // 清理缓存
+        *self.sentences.lock().await = Segments::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(sn: u32, pgs: &str, rg: Option<[u32; 2]>, ls: bool, text: &str) -> ResultText {
+        ResultText {
+            sn,
+            ls,
+            pgs: Some(pgs.to_string()),
+            rg,
+            ws: vec![WordSlice { cw: vec![WordCell { w: text.to_string() }] }],
+        }
+    }
+
+    #[test]
+    fn merge_commits_at_sentence_end() {
+        let mut segments = Segments::default();
+        assert_eq!(segments.merge(result(1, "apd", None, false, "今天")), ("".into(), "今天".into()));
+        assert_eq!(segments.merge(result(2, "apd", None, false, "天汽")), ("".into(), "今天天汽".into()));
+        // 替换 2..2，句子未结束，不定稿
+        assert_eq!(segments.merge(result(3, "rpl", Some([2, 2]), false, "天气")), ("".into(), "今天天气".into()));
+        assert_eq!(segments.merge(result(4, "apd", None, false, "不错。")), ("今天天气不错。".into(), "".into()));
+        assert_eq!(segments.merge(result(5, "apd", None, false, "出去")), ("".into(), "出去".into()));
+        // 最后一段，全部定稿
+        assert_eq!(segments.merge(result(6, "apd", None, true, "走走")), ("出去走走".into(), "".into()));
+        assert!(segments.pending.is_empty() && segments.committed.is_empty());
+    }
+
+    #[test]
+    fn merge_skips_decimal_point() {
+        let mut segments = Segments::default();
+        assert_eq!(segments.merge(result(1, "apd", None, false, "好的。")), ("好的。".into(), "".into()));
+        assert_eq!(segments.merge(result(2, "apd", None, false, "版本 3.")), ("".into(), "版本 3.".into()));
+        assert_eq!(segments.merge(result(3, "apd", None, false, "5 发布了！")), ("版本 3.5 发布了！".into(), "".into()));
+        assert_eq!(segments.merge(result(4, "apd", None, false, "Hello. ")), ("Hello. ".into(), "".into()));
+        assert_eq!(segments.merge(result(5, "apd", None, false, "再见")), ("".into(), "再见".into()));
+    }
+
+    #[test]
+    fn merge_replacement_over_committed_segments() {
+        let mut segments = Segments::default();
+        assert_eq!(segments.merge(result(1, "apd", None, false, "你好。")), ("你好。".into(), "".into()));
+        assert_eq!(segments.merge(result(2, "apd", None, false, "我是")), ("".into(), "我是".into()));
+        // 范围回溯到已提交的第 1 段，已提交的文本不再输出
+        assert_eq!(segments.merge(result(3, "rpl", Some([1, 2]), false, "你好。我是小明")), ("".into(), "我是小明".into()));
+        // 已提交部分的修正不能撤回，只输出其后的文本
+        assert_eq!(segments.merge(result(4, "rpl", Some([3, 3]), false, "你好！我是小明。")), ("我是小明。".into(), "".into()));
+        // 再次回溯：第 4 段已全部提交
+        assert_eq!(segments.merge(result(5, "rpl", Some([4, 4]), false, "你好！我是小明。")), ("".into(), "".into()));
+        assert_eq!(segments.merge(result(6, "apd", None, true, "")), ("".into(), "".into()));
+        assert!(segments.committed.is_empty());
     }
 }
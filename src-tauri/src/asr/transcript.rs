@@ -0,0 +1,307 @@
+//! 识别文本输出
+//!
+//! 服务商只提交当前句的完整识别文本，由 Transcript 统一做后处理（文本规整、替换规则、标点风格、语音指令、大模型润色、翻译等），
+//! 再与该句已输出的文本比对，以退格 + 新增的方式写入实时输出方式（模拟键盘输入）；
+//! 其他输出方式（剪贴板、文件等）只接收定稿的句子。
+//! 已定稿（commit）的句子不再修改，修正范围限制在当前句内。
+//! 润色和翻译只作用于定稿的句子：识别过程中先输出原文，定稿后再以差量替换为结果。
+
+use crate::models::config::{
+    AppConfig, CommandAction, HotWord, ItnConfig, PostEditConfig, PunctuationConfig, TranslationConfig, TranslationOutput,
+};
+use crate::utils::output::OutputSink;
+use crate::utils::spoken_command::{self, CommandSet};
+use crate::utils::text_diff::compute_diff;
+use crate::utils::text_rules::RuleSet;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// 定稿的一句（时间为相对会话开始的毫秒数，服务商未提供时为 None）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    pub text: String,
+    pub start_ms: Option<u64>,
+    pub end_ms: Option<u64>,
+    /// 说话人（开启说话人分离且服务商支持时）
+    pub speaker: Option<String>,
+}
+
+/// 识别结果事件（供控制接口推送）
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEvent {
+    /// 当前句的中间结果（处理后）
+    Partial { text: String },
+    /// 定稿的一句
+    Final(Segment),
+}
+
+pub struct Transcript {
+    sink: Arc<dyn OutputSink>,
+    /// 识别后按热词修正写法（服务商热词不带权重时）
+    vocabulary: Vec<HotWord>,
+    itn: Option<ItnConfig>,
+    rules: RuleSet,
+    punctuation: Option<PunctuationConfig>,
+    commands: Option<CommandSet>,
+    post_edit: Option<PostEditConfig>,
+    translation: Option<TranslationConfig>,
+    /// 语音指令请求停止听写
+    stop_requested: Arc<AtomicBool>,
+    /// 本次会话定稿的句子（供历史记录、字幕使用）
+    segments: Arc<Mutex<Vec<Segment>>>,
+    events: broadcast::Sender<TranscriptEvent>,
+    /// 已定稿的输出文本
+    committed: String,
+    /// 当前句已输出的文本（处理后）
+    pending: String,
+}
+
+impl Transcript {
+    /// 按配置构建，app 为会话开始时的前台应用（用于规则作用域）
+    pub fn new(
+        sink: Arc<dyn OutputSink>,
+        config: &AppConfig,
+        app: Option<&str>,
+        stop_requested: Arc<AtomicBool>,
+        segments: Arc<Mutex<Vec<Segment>>>,
+        events: broadcast::Sender<TranscriptEvent>,
+    ) -> Self {
+        let weighted = crate::asr::manager::weighted_hotwords(&config.asr.provider);
+        Self {
+            sink,
+            vocabulary: if weighted { Vec::new() } else { config.vocabulary.clone() },
+            itn: Some(config.itn.clone()).filter(|c| c.enabled),
+            rules: RuleSet::compile(&config.replace_rules, app),
+            punctuation: Some(config.punctuation.clone()).filter(|c| c.enabled),
+            commands: CommandSet::new(&config.voice_commands),
+            post_edit: Some(config.post_edit.clone()).filter(|p| p.enabled),
+            translation: Some(config.translation.clone()).filter(|t| t.enabled),
+            stop_requested,
+            segments,
+            events,
+            committed: String::new(),
+            pending: String::new(),
+        }
+    }
+
+    /// 更新当前句（未定稿）的完整识别文本
+    pub fn update(&mut self, text: &str) {
+        let output = self.process(text, false);
+        if output != self.pending {
+            let _ = self.events.send(TranscriptEvent::Partial { text: output.clone() });
+        }
+        self.emit(output);
+    }
+
+    /// 只推送当前句的中间结果，不输出（服务商设置为不实时输入中间结果时）
+    pub fn preview(&mut self, text: &str) {
+        let output = self.process(text, false);
+        let _ = self.events.send(TranscriptEvent::Partial { text: output });
+    }
+
+    /// 当前句定稿的第一步：整句为语音指令时撤回已输出的文字并执行指令，返回 None；
+    /// 否则输出处理后的文本并返回，由调用方润色、翻译后交给 finish_commit
+    fn begin_commit(&mut self, text: &str) -> Option<String> {
+        let action = self.commands.as_ref().and_then(|c| c.match_command(text)).cloned();
+        match action {
+            Some(action) => {
+                self.emit(String::new());
+                self.execute(action);
+                None
+            }
+            None => {
+                let output = self.process(text, true);
+                self.emit(output);
+                Some(self.pending.clone())
+            }
+        }
+    }
+
+    /// 当前句定稿的第二步：以差量替换为最终文本，记录并输出定稿的句子
+    fn finish_commit(&mut self, output: String, timing: Option<(u64, u64)>, speaker: Option<String>) {
+        self.emit(output);
+        let pending = std::mem::take(&mut self.pending);
+        if !pending.trim().is_empty() {
+            let segment = Segment {
+                text: pending.clone(),
+                start_ms: timing.map(|t| t.0),
+                end_ms: timing.map(|t| t.1),
+                speaker,
+            };
+            let _ = self.events.send(TranscriptEvent::Final(segment.clone()));
+            self.segments.lock().unwrap().push(segment);
+            if !self.sink.is_live() {
+                self.output(&pending);
+            }
+        }
+        self.committed.push_str(&pending);
+    }
+
+    /// 热词修正 → 文本规整 → 替换规则 → 标点风格
+    fn process(&self, text: &str, is_final: bool) -> String {
+        let text = crate::utils::vocabulary::apply(text, &self.vocabulary);
+        let text = match &self.itn {
+            Some(itn) => crate::utils::itn::normalize(&text, itn),
+            None => text,
+        };
+        let text = self.rules.apply(&text);
+        match &self.punctuation {
+            Some(p) => crate::utils::punctuation::normalize(&text, p, &self.committed, is_final),
+            None => text,
+        }
+    }
+
+    fn execute(&mut self, action: CommandAction) {
+        log::info!("语音指令: {:?}", action);
+        match action {
+            CommandAction::NewLine => self.insert("\n"),
+            CommandAction::NewParagraph => self.insert("\n\n"),
+            CommandAction::Insert(text) => self.insert(&text),
+            CommandAction::DeleteWord => self.delete(spoken_command::last_word_len(&self.committed)),
+            CommandAction::DeleteSentence => self.delete(spoken_command::last_sentence_len(&self.committed)),
+            CommandAction::SelectAll | CommandAction::Undo if !self.sink.is_live() => {
+                log::warn!("当前输出方式不支持该指令");
+            }
+            CommandAction::SelectAll => {
+                // 全选/撤销后无法确定已输出内容，不再跟踪
+                self.sink.revise(0, "\x01");
+                self.committed.clear();
+            }
+            CommandAction::Undo => {
+                self.sink.revise(0, "\x1a");
+                self.committed.clear();
+            }
+            CommandAction::StopDictation => self.stop_requested.store(true, Ordering::SeqCst),
+        }
+    }
+
+    fn insert(&mut self, text: &str) {
+        if self.sink.is_live() {
+            self.sink.revise(0, text);
+        } else {
+            self.output(text);
+        }
+        self.committed.push_str(text);
+    }
+
+    fn delete(&mut self, count: usize) {
+        if !self.sink.is_live() {
+            log::warn!("当前输出方式不支持删除已输出的文字");
+            return;
+        }
+        self.sink.revise(count, "");
+        let keep = self.committed.chars().count() - count;
+        self.committed = self.committed.chars().take(keep).collect();
+    }
+
+    fn emit(&mut self, output: String) {
+        if output == self.pending {
+            return;
+        }
+        if self.sink.is_live() {
+            let (backspace, addition) = compute_diff(&self.pending, &output);
+            self.sink.revise(backspace, &addition);
+        }
+        self.pending = output;
+    }
+
+    /// 写入非实时输出方式，失败只记录日志
+    fn output(&self, text: &str) {
+        if let Err(e) = self.sink.commit(text) {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+/// 供服务商收发任务共享的 Transcript
+///
+/// 润色和翻译请求期间不持有锁，只在输出时短暂加锁，避免慢速的大模型接口阻塞其他任务
+#[derive(Clone)]
+pub struct SharedTranscript(Arc<tokio::sync::Mutex<Transcript>>);
+
+impl SharedTranscript {
+    pub fn new(transcript: Transcript) -> Self {
+        Self(Arc::new(tokio::sync::Mutex::new(transcript)))
+    }
+
+    /// 更新当前句（未定稿）的完整识别文本
+    pub async fn update(&self, text: &str) {
+        self.0.lock().await.update(text);
+    }
+
+    /// 只推送当前句的中间结果，不输出
+    pub async fn preview(&self, text: &str) {
+        self.0.lock().await.preview(text);
+    }
+
+    /// 当前句定稿
+    pub async fn commit(&self, text: &str) {
+        self.commit_timed(text, None, None).await;
+    }
+
+    /// 当前句定稿，附带服务商给出的起止时间（毫秒）和说话人
+    pub async fn commit_timed(&self, text: &str, timing: Option<(u64, u64)>, speaker: Option<String>) {
+        let (mut output, post_edit, translation) = {
+            let mut transcript = self.0.lock().await;
+            let Some(output) = transcript.begin_commit(text) else { return };
+            (output, transcript.post_edit.clone(), transcript.translation.clone())
+        };
+        if !output.trim().is_empty() {
+            if let Some(config) = post_edit {
+                output = self.post_edit(&config, output).await;
+            }
+            if let Some(config) = translation {
+                output = translate(&config, output).await;
+            }
+        }
+        self.0.lock().await.finish_commit(output, timing, speaker);
+    }
+
+    /// 大模型润色：流式替换已输出的文字，失败或超时回退为原文
+    async fn post_edit(&self, config: &PostEditConfig, raw: String) -> String {
+        let timeout = std::time::Duration::from_millis(config.timeout_ms);
+        let edit = crate::utils::post_edit::stream(config, &raw, |partial| {
+            // 中间结果尽力输出，锁被占用时跳过（最终结果由 finish_commit 写入）
+            if let Ok(mut transcript) = self.0.try_lock() {
+                transcript.emit(partial.to_string());
+            }
+        });
+        match tokio::time::timeout(timeout, edit).await {
+            Ok(Ok(edited)) if !edited.is_empty() => edited,
+            Ok(Ok(_)) => raw,
+            Ok(Err(e)) => {
+                log::warn!("{}，使用原文", e);
+                raw
+            }
+            Err(_) => {
+                log::warn!("润色超时，使用原文");
+                raw
+            }
+        }
+    }
+}
+
+/// 翻译定稿的句子，失败或超时保留原文
+async fn translate(config: &TranslationConfig, original: String) -> String {
+    let timeout = std::time::Duration::from_millis(config.timeout_ms);
+    let result = tokio::time::timeout(timeout, crate::utils::translate::translate(config, &original)).await;
+    let translated = match result {
+        Ok(Ok(translated)) if !translated.is_empty() => translated,
+        Ok(Ok(_)) => return original,
+        Ok(Err(e)) => {
+            log::warn!("{}，保留原文", e);
+            return original;
+        }
+        Err(_) => {
+            log::warn!("翻译超时，保留原文");
+            return original;
+        }
+    };
+    match config.output {
+        TranslationOutput::Replace => translated,
+        TranslationOutput::Append => format!("{} ({})", original, translated),
+    }
+}
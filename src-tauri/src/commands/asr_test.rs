@@ -1,9 +1,17 @@
 use crate::models::config::{AsrConfig, DoubaoConfig, FunasrConfig, XunfeiConfig};
+use crate::models::state::AppState;
 
 /// 测试 ASR 配置
-/// 尝试建立 WebSocket 连接来验证配置是否正确
+/// 尝试建立 WebSocket 连接来验证配置是否正确（掩码的密钥使用已保存的值）
 #[tauri::command]
-pub async fn test_asr_config(config: AsrConfig) -> Result<(), String> {
+pub async fn test_asr_config(state: tauri::State<'_, AppState>, config: AsrConfig) -> Result<(), String> {
+    let config = {
+        let current = state.config.lock().unwrap().clone();
+        let mut full = current.clone();
+        full.asr = config;
+        full.restore_masked(&current);
+        full.asr
+    };
     match config.provider.as_str() {
         "xunfei" => test_xunfei_config(&config.xunfei).await,
         "doubao" => test_doubao_config(&config.doubao).await,
@@ -117,9 +125,9 @@ async fn test_funasr_config(config: &FunasrConfig) -> Result<(), String> {
 
     match tokio::time::timeout(std::time::Duration::from_secs(5), connect_async(&ws_url)).await {
         Ok(Ok((mut ws, _))) => {
-            // 握手成功即视为可达；发送 finish 让服务端主动结束会话
+            // 握手成功即视为可达；未发送识别配置，直接结束
             let _ = ws
-                .send(Message::Text("{\"cmd\":\"finish\"}".to_string()))
+                .send(Message::Text("{\"is_speaking\":false}".to_string()))
                 .await;
             let _ = ws.close(None).await;
             Ok(())
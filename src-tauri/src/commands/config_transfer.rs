@@ -0,0 +1,114 @@
+use crate::models::config::AppConfig;
+use crate::models::managed::ManagedDefaults;
+use crate::models::migration;
+use crate::models::state::AppState;
+use crate::models::validation::{self, ConfigError};
+use crate::utils::secrets;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, State};
+
+/// 导入预览中的一项变化（密钥已掩码）
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub key: String,
+    pub old: Option<Value>,
+    pub new: Option<Value>,
+    /// 密钥项：old/new 为掩码，只表示密钥有变化
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportPreview {
+    pub changes: Vec<ConfigChange>,
+    /// 被锁定、导入后仍保持统一下发值的项
+    pub locked: Vec<String>,
+}
+
+/// 导出配置到文件，include_secrets 为 false 时不包含密钥
+#[tauri::command]
+pub fn export_config(state: State<AppState>, path: String, include_secrets: bool) -> Result<(), String> {
+    let config = state.config.lock().unwrap().clone();
+    let mut config = if include_secrets { config } else { config.without_secrets() };
+    config.version = migration::CONFIG_VERSION;
+    let content = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("写入 {} 失败: {}", path, e))?;
+    log::info!("已导出配置: {}", path);
+    Ok(())
+}
+
+/// 校验待导入的配置并列出与当前配置的差异
+#[tauri::command]
+pub fn preview_config_import(state: State<AppState>, path: String) -> Result<ImportPreview, ConfigError> {
+    let current = state.config.lock().unwrap().clone();
+    let (imported, locked) = read_import(&path, &current)?;
+
+    // 按原值比较，按掩码后的值展示，密钥变化也能列出
+    let to_flat = |config: &AppConfig| serde_json::to_value(config).map(|v| flatten(&v)).map_err(|e| e.to_string());
+    let (old, new) = (to_flat(&current)?, to_flat(&imported)?);
+    let (old_masked, new_masked) = (to_flat(&current.masked())?, to_flat(&imported.masked())?);
+    let is_mask = |v: Option<&Value>| v.and_then(Value::as_str) == Some(secrets::MASK);
+    let changes = old
+        .keys()
+        .chain(new.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .filter(|key| key.as_str() != "version" && old.get(*key) != new.get(*key))
+        .map(|key| ConfigChange {
+            key: key.clone(),
+            old: old_masked.get(key).cloned(),
+            new: new_masked.get(key).cloned(),
+            secret: is_mask(old_masked.get(key)) || is_mask(new_masked.get(key)),
+        })
+        .collect();
+    Ok(ImportPreview { changes, locked })
+}
+
+/// 导入配置文件（文件中未包含的密钥沿用当前值）
+#[tauri::command]
+pub fn import_config(app: AppHandle, state: State<AppState>, path: String) -> Result<(), ConfigError> {
+    let current = state.config.lock().unwrap().clone();
+    let (imported, _) = read_import(&path, &current)?;
+    super::settings::sync_config(app, state, imported)?;
+    log::info!("已导入配置: {}", path);
+    Ok(())
+}
+
+/// 被统一下发配置锁定的项，界面据此禁用对应设置
+#[tauri::command]
+pub fn get_locked_keys() -> Vec<String> {
+    ManagedDefaults::load().map(|m| m.locked).unwrap_or_default()
+}
+
+/// 读取并校验导入文件，返回应用锁定项后的配置和被锁定覆盖的项
+fn read_import(path: &str, current: &AppConfig) -> Result<(AppConfig, Vec<String>), ConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path, e))?;
+    let mut raw: Value = serde_json::from_str(&content).map_err(|e| format!("配置格式错误: {}", e))?;
+    // 与启动时读取配置一致：文件未包含的项使用统一下发的默认值，锁定项使用下发的值
+    let locked = ManagedDefaults::load().map(|m| m.apply(&mut raw)).unwrap_or_default();
+    let mut config = migration::parse(raw)?;
+    config.keep_secrets(current);
+    validation::validate(&config)?;
+    Ok((config, locked))
+}
+
+/// 展开为 点分路径 -> 值（数组整体作为一个值）
+fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    fn walk(value: &Value, prefix: &str, out: &mut BTreeMap<String, Value>) {
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                for (key, v) in map {
+                    let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                    walk(v, &path, out);
+                }
+            }
+            _ => {
+                out.insert(prefix.to_string(), value.clone());
+            }
+        }
+    }
+    let mut out = BTreeMap::new();
+    walk(value, "", &mut out);
+    out
+}
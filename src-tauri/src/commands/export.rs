@@ -0,0 +1,22 @@
+use crate::models::state::AppState;
+use crate::utils::export::{self, ExportFormat, Session};
+use std::path::Path;
+use tauri::State;
+
+/// 导出会话：source 为会话存档（JSON）路径，为空时导出最近一次听写
+#[tauri::command]
+pub fn export_session(
+    state: State<AppState>,
+    source: Option<String>,
+    format: ExportFormat,
+    output: String,
+) -> Result<(), String> {
+    let session = match source.as_deref().filter(|s| !s.is_empty()) {
+        Some(path) => Session::load(Path::new(path))?,
+        None => state.asr_manager.last_session(),
+    };
+    if session.segments.is_empty() {
+        return Err("没有可导出的内容".to_string());
+    }
+    export::export(&session, format, Path::new(&output))
+}
@@ -0,0 +1,17 @@
+use crate::workflow::funasr_service::{FunasrService, ServiceStatus};
+use tauri::State;
+
+#[tauri::command]
+pub fn start_funasr_service(service: State<FunasrService>) -> Result<(), String> {
+    service.start()
+}
+
+#[tauri::command]
+pub fn stop_funasr_service(service: State<FunasrService>) {
+    service.stop();
+}
+
+#[tauri::command]
+pub fn get_funasr_service_status(service: State<FunasrService>) -> ServiceStatus {
+    service.status()
+}
@@ -1,3 +1,6 @@
 pub mod settings;
 pub mod asr_test;
 pub mod theme;
+pub mod funasr_service;
+pub mod export;
+pub mod config_transfer;
@@ -1,6 +1,6 @@
 use tauri::{AppHandle, Manager, WebviewWindow, Emitter};
 use tauri_plugin_autostart::ManagerExt;
-use crate::models::{state::AppState, config::AppConfig};
+use crate::models::{state::AppState, config::{AppConfig, AsrLanguage}, validation::{self, ConfigError}};
 
 const LABEL: &str = "settings";
 const URL: &str = "/src/settings.html";
@@ -55,35 +55,143 @@ pub fn close_settings_window(app: AppHandle) {
 
 #[tauri::command]
 pub fn get_config(state: tauri::State<AppState>) -> Result<AppConfig, String> {
-    Ok(state.config.lock().unwrap().clone())
+    Ok(state.config.lock().unwrap().masked())
 }
 
 #[tauri::command]
 pub fn sync_config(
     app: AppHandle,
     state: tauri::State<AppState>,
-    new_config: AppConfig,
-) -> Result<(), String> {
+    mut new_config: AppConfig,
+) -> Result<(), ConfigError> {
+    new_config.restore_masked(&state.config.lock().unwrap());
+    apply_config(&app, &state, new_config)
+}
+
+/// 保存并应用配置
+///
+/// 先校验（错误按字段返回），再依次应用快捷键、开机自启动，最后保存；
+/// 任一步失败时撤销已应用的变化，配置保持不变
+pub fn apply_config(app: &AppHandle, state: &AppState, mut new_config: AppConfig) -> Result<(), ConfigError> {
     let old = state.config.lock().unwrap().clone();
-    state.update_config(&app, new_config.clone())?;
-    
+    let locked = new_config.enforce_locked();
+    if !locked.is_empty() {
+        log::warn!("以下配置项已被锁定，保持统一下发的值: {:?}", locked);
+    }
+    validation::validate(&new_config)?;
+
     // 处理快捷键变化
-    if old.shortcut != new_config.shortcut {
-        let _ = crate::utils::shortcut::update_shortcut(&app, &new_config.shortcut);
+    let shortcuts_changed = old.shortcut != new_config.shortcut
+        || old.undo_shortcut != new_config.undo_shortcut
+        || old.language_shortcut != new_config.language_shortcut
+        || old.profile_shortcut != new_config.profile_shortcut;
+    let rollback_shortcuts = || {
+        if shortcuts_changed {
+            if let Err(e) = crate::utils::shortcut::update_shortcuts(app, &old) {
+                log::error!("恢复快捷键失败: {}", e);
+            }
+        }
+    };
+    if shortcuts_changed {
+        if let Err(e) = crate::utils::shortcut::update_shortcuts(app, &new_config) {
+            rollback_shortcuts();
+            return Err(ConfigError::field("shortcut", e));
+        }
     }
-    
+
     // 处理开机自启动变化
-    if old.auto_start != new_config.auto_start {
-        let autostart_manager = app.autolaunch();
-        if new_config.auto_start {
-            autostart_manager.enable().map_err(|e| format!("启用开机自启动失败: {}", e))?;
-        } else {
-            autostart_manager.disable().map_err(|e| format!("禁用开机自启动失败: {}", e))?;
+    let autostart_changed = old.auto_start != new_config.auto_start;
+    if autostart_changed {
+        if let Err(e) = set_autostart(app, new_config.auto_start) {
+            rollback_shortcuts();
+            return Err(ConfigError::field("auto_start", e));
+        }
+    }
+
+    if let Err(e) = state.update_config(app, new_config.clone()) {
+        if autostart_changed {
+            if let Err(e) = set_autostart(app, old.auto_start) {
+                log::error!("恢复开机自启动失败: {}", e);
+            }
         }
+        rollback_shortcuts();
+        return Err(e.into());
+    }
+    
+    // 方案列表变化时刷新托盘菜单
+    if old.active_profile != new_config.active_profile
+        || old.profiles.iter().map(|p| &p.name).ne(new_config.profiles.iter().map(|p| &p.name))
+    {
+        crate::tray::refresh_profiles(app);
     }
     
     // 广播配置更新事件给所有窗口，通知所有 useConfig 实例同步
-    let _ = app.emit("config-updated", new_config);
+    let _ = app.emit("config-updated", new_config.masked());
     
     Ok(())
 }
+
+fn set_autostart(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autostart_manager = app.autolaunch();
+    if enabled {
+        autostart_manager.enable().map_err(|e| format!("启用开机自启动失败: {}", e))
+    } else {
+        autostart_manager.disable().map_err(|e| format!("禁用开机自启动失败: {}", e))
+    }
+}
+
+/// 各服务商支持的识别语言
+#[tauri::command]
+pub fn get_language_capabilities() -> Vec<crate::asr::language::ProviderLanguages> {
+    crate::asr::language::capabilities()
+}
+
+/// 切换到当前服务商支持的下一个识别语言（下次录音生效）
+#[tauri::command]
+pub fn cycle_language(app: AppHandle, state: tauri::State<AppState>) -> Result<AsrLanguage, String> {
+    let mut config = state.config.lock().unwrap().clone();
+    // 按下次听写实际使用的方案（前台应用匹配的方案优先）切换，该方案指定了语言时切换方案中的语言
+    let foreground = crate::utils::window_ext::foreground_app();
+    let effective = config.effective(foreground.as_deref());
+    let language = crate::asr::language::next(&effective.asr.provider, &effective.asr.language);
+    let name = config.profile_for(foreground.as_deref())
+        .filter(|p| p.language.is_some())
+        .map(|p| p.name.clone());
+    let profile = name.and_then(|name| config.profiles.iter_mut().find(|p| p.name == name));
+    match profile {
+        Some(profile) => profile.language = Some(language.clone()),
+        None => config.asr.language = language.clone(),
+    }
+    state.update_config(&app, config.clone())?;
+    log::info!("识别语言切换为 {:?}", language);
+    let _ = app.emit("config-updated", config.masked());
+    Ok(language)
+}
+
+/// 切换当前配置方案（空字符串表示只使用全局配置）
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    let mut config = state.config.lock().unwrap().clone();
+    if !name.is_empty() && !config.profiles.iter().any(|p| p.name == name) {
+        return Err(format!("配置方案不存在: {}", name));
+    }
+    config.active_profile = name;
+    state.update_config(&app, config.clone())?;
+    log::info!("配置方案切换为 {:?}", config.active_profile);
+    crate::tray::refresh_profiles(&app);
+    let _ = app.emit("config-updated", config.masked());
+    Ok(())
+}
+
+/// 按顺序切换到下一个配置方案（最后一个之后回到全局配置）
+#[tauri::command]
+pub fn cycle_profile(app: AppHandle, state: tauri::State<'_, AppState>) -> Result<String, String> {
+    let next = {
+        let config = state.config.lock().unwrap();
+        let names: Vec<&str> = std::iter::once("").chain(config.profiles.iter().map(|p| p.name.as_str())).collect();
+        let pos = names.iter().position(|n| *n == config.active_profile).unwrap_or(0);
+        names[(pos + 1) % names.len()].to_string()
+    };
+    switch_profile(app, state, next.clone())?;
+    Ok(next)
+}
@@ -44,6 +44,28 @@ pub fn set_recording(
     Ok(())
 }
 
+/// 按主题设置尺寸显示录音窗口并切换录音状态（快捷键和命令行 --toggle 使用）
+pub fn toggle_recording(app: &tauri::AppHandle) {
+    let state = app.state::<AppState>();
+    let (width, height) = match state.config.lock().unwrap().theme {
+        crate::models::config::Theme::Default => (Some(240), Some(150)),
+        crate::models::config::Theme::Google => (Some(400), Some(100)),
+    };
+    show_window(app.clone(), width, height);
+    crate::utils::recording_state::toggle(&state, app);
+}
+
+#[tauri::command]
+pub fn cancel_recording(app: tauri::AppHandle, state: State<AppState>) -> Result<(), String> {
+    crate::utils::recording_state::cancel(&state, &app);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn undo_last_dictation(state: State<AppState>) -> Result<usize, String> {
+    crate::workflow::input_simulator::undo_last_dictation(&state)
+}
+
 #[tauri::command]
 pub fn hide_and_stop_recording(app: tauri::AppHandle) {
     window_ext::hide(&app, "main");
@@ -53,3 +75,19 @@ pub fn hide_and_stop_recording(app: tauri::AppHandle) {
 pub fn get_recording_state(state: State<AppState>) -> bool {
     *state.is_recording.lock().unwrap()
 }
+
+/// 最近一次听写定稿的句子（含时间信息）
+#[tauri::command]
+pub fn get_last_segments(state: State<AppState>) -> Vec<crate::asr::transcript::Segment> {
+    state.asr_manager.last_session().segments
+}
+
+#[tauri::command]
+pub fn start_meeting(app: tauri::AppHandle, state: State<AppState>) -> Result<(), String> {
+    crate::utils::recording_state::start_meeting(&state, &app)
+}
+
+#[tauri::command]
+pub fn stop_meeting(app: tauri::AppHandle, state: State<AppState>) -> bool {
+    crate::utils::recording_state::stop_meeting(&state, &app)
+}
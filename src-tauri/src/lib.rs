@@ -11,6 +11,7 @@ use models::state::AppState;
 use tauri::Manager;
 use tauri_plugin_autostart::ManagerExt;
 use tauri_plugin_global_shortcut::ShortcutState;
+use utils::shortcut::ShortcutAction;
 use std::sync::{Arc, Mutex};
 
 const DEFAULT_SHORTCUT: &str = "Shift+E";
@@ -19,22 +20,51 @@ const DEFAULT_SHORTCUT: &str = "Shift+E";
 pub fn run() {
     env_logger::init();
     
+    // 命令行导出会话存档，不启动界面
+    let args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--export") {
+        if let Err(e) = utils::export::run_cli(&args) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // 单实例：已有实例在运行时把参数转发给它后退出
+    if utils::instance::forward(&args) {
+        return;
+    }
+    
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, None))
         .plugin(
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, _shortcut, event| {
-                    if event.state == ShortcutState::Pressed {
-                        // 根据当前主题设置窗口尺寸
-                        let state = app.state::<AppState>();
-                        let (width, height) = match state.config.lock().unwrap().theme {
-                            models::config::Theme::Default => (Some(240), Some(150)),
-                            models::config::Theme::Google => (Some(400), Some(100)),
-                        };
-                        commands::theme::show_window(app.clone(), width, height);
-                        crate::utils::recording_state::toggle(&state, &app);
+                .with_handler(|app, shortcut, event| {
+                    if event.state != ShortcutState::Pressed {
+                        return;
+                    }
+                    let state = app.state::<AppState>();
+                    let action = utils::shortcut::action_of(&state.config.lock().unwrap(), shortcut);
+                    match action {
+                        ShortcutAction::UndoDictation => {
+                            if let Err(e) = workflow::input_simulator::undo_last_dictation(&state) {
+                                log::warn!("撤销听写失败: {}", e);
+                            }
+                        }
+                        ShortcutAction::CycleLanguage => {
+                            if let Err(e) = commands::settings::cycle_language(app.clone(), state) {
+                                log::warn!("切换识别语言失败: {}", e);
+                            }
+                        }
+                        ShortcutAction::CycleProfile => {
+                            if let Err(e) = commands::settings::cycle_profile(app.clone(), state) {
+                                log::warn!("切换配置方案失败: {}", e);
+                            }
+                        }
+                        ShortcutAction::ToggleRecording => commands::theme::toggle_recording(app),
                     }
                 })
                 .build(),
@@ -46,11 +76,28 @@ pub fn run() {
             commands::theme::quit_app,
             commands::theme::get_recording_state,
             commands::theme::set_recording,
+            commands::theme::cancel_recording,
+            commands::theme::undo_last_dictation,
+            commands::theme::get_last_segments,
+            commands::theme::start_meeting,
+            commands::theme::stop_meeting,
             commands::settings::open_settings,
             commands::settings::close_settings_window,
             commands::settings::get_config,
             commands::settings::sync_config,
+            commands::settings::get_language_capabilities,
+            commands::settings::cycle_language,
+            commands::settings::switch_profile,
+            commands::settings::cycle_profile,
             commands::asr_test::test_asr_config,
+            commands::export::export_session,
+            commands::config_transfer::export_config,
+            commands::config_transfer::preview_config_import,
+            commands::config_transfer::import_config,
+            commands::config_transfer::get_locked_keys,
+            commands::funasr_service::start_funasr_service,
+            commands::funasr_service::stop_funasr_service,
+            commands::funasr_service::get_funasr_service_status,
         ])
         .setup(|app| {
             let config = Arc::new(Mutex::new(AppConfig::default()));
@@ -84,6 +131,20 @@ pub fn run() {
                 if utils::shortcut::init_shortcut(app, sc).is_ok() { break; }
                 if i == 1 { log::error!("快捷键注册失败，应用继续运行"); }
             }
+            utils::shortcut::register_extra(app, &state.config.lock().unwrap());
+            
+            // 本地 FunASR 服务进程管理
+            let log_path = app.path().app_log_dir().ok().map(|dir| {
+                let _ = std::fs::create_dir_all(&dir);
+                dir.join("funasr-service.log")
+            });
+            let funasr_service = workflow::funasr_service::FunasrService::new(
+                state.config.clone(),
+                app.handle().clone(),
+                log_path,
+            );
+            app.manage(funasr_service.clone());
+            workflow::funasr_service::init(funasr_service);
             
             // 克隆 state 用于后续使用
             let state_clone = Arc::new(state.inner().clone());
@@ -91,11 +152,25 @@ pub fn run() {
             tray::setup_tray(app)?;
             workflow::recorder::init_recorder(state_clone.clone());
             workflow::input_simulator::init_input_simulator(state_clone.clone());
-            workflow::asr_controller::init_asr_controller(state_clone.clone());
+            workflow::asr_controller::init_asr_controller(state_clone.clone(), app.handle().clone());
+            workflow::meeting::init(state_clone.clone(), app.handle().clone());
             workflow::global_input::init(state_clone, app.handle().clone());
+            workflow::config_watcher::init(app.handle().clone());
+            workflow::control_api::init(app.handle().clone());
+
+            // 执行启动参数中的操作（如 --profile coding），并接收之后启动的实例转发的参数
+            let args: Vec<String> = std::env::args().collect();
+            utils::instance::handle(app.handle(), &args);
+            utils::instance::listen(app.handle().clone());
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // 退出时停止本地 FunASR 服务
+            if let tauri::RunEvent::Exit = event {
+                app.state::<workflow::funasr_service::FunasrService>().stop();
+            }
+        });
 }
@@ -1,11 +1,13 @@
 use std::collections::VecDeque;
 use std::sync::{Condvar, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 pub struct AudioBuffer {
     data: Mutex<VecDeque<i16>>,
     cond: Condvar,
     is_finished: AtomicBool,
+    /// 已读取的采样数
+    consumed: AtomicU64,
 }
 
 impl AudioBuffer {
@@ -14,6 +16,7 @@ impl AudioBuffer {
             data: Mutex::new(VecDeque::new()),
             cond: Condvar::new(),
             is_finished: AtomicBool::new(false),
+            consumed: AtomicU64::new(0),
         }
     }
 
@@ -39,7 +42,8 @@ impl AudioBuffer {
                 break;
             }
         }
-        
+        self.consumed.fetch_add(count as u64, Ordering::SeqCst);
+
         count
     }
 
@@ -52,6 +56,12 @@ impl AudioBuffer {
         let mut data = self.data.lock().unwrap();
         data.clear();
         self.is_finished.store(false, Ordering::SeqCst);
+        self.consumed.store(0, Ordering::SeqCst);
+    }
+
+    /// clear 之后已读取的采样数（即读取方在录音中的位置）
+    pub fn consumed(&self) -> u64 {
+        self.consumed.load(Ordering::SeqCst)
     }
 
     pub fn is_finished(&self) -> bool {
@@ -74,7 +84,8 @@ impl Default for AudioBuffer {
 }
 
 /// 文本输入队列 - 每个字符是一个独立的队列元素
-/// 特殊字符：\x08 (ASCII退格) 表示退格，'\n' 表示回车
+/// 特殊字符：\x08 (ASCII退格) 表示退格，'\n' 表示回车，
+/// \x01 (Ctrl+A) 表示全选，\x1a (Ctrl+Z) 表示撤销
 pub struct TextBuffer {
     chars: Mutex<VecDeque<char>>,
     cond: Condvar,
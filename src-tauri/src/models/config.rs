@@ -1,10 +1,31 @@
+use super::managed::ManagedDefaults;
+use super::migration;
+use super::validation::{self, ConfigError};
+use crate::utils::secrets;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
+    /// 配置版本（见 migration::CONFIG_VERSION，保存时写入）
+    #[serde(default)]
+    pub version: u32,
+
     #[serde(default = "default_shortcut")]
     pub shortcut: String,
 
+    /// 撤销上一次听写的快捷键（空表示不启用；Windows 以外的平台不检查焦点是否仍在听写的窗口）
+    #[serde(default)]
+    pub undo_shortcut: String,
+
+    /// 切换识别语言的快捷键（空表示不启用）
+    #[serde(default)]
+    pub language_shortcut: String,
+
+    /// 切换配置方案的快捷键（空表示不启用）
+    #[serde(default)]
+    pub profile_shortcut: String,
+
     #[serde(default)]
     pub theme: Theme,
 
@@ -14,6 +35,476 @@ pub struct AppConfig {
     /// ASR 配置（多服务商，同时存储）
     #[serde(default)]
     pub asr: AsrConfig,
+
+    /// 取消录音配置
+    #[serde(default)]
+    pub cancel: CancelConfig,
+
+    /// 自定义词汇（热词），由各服务商映射到自身的热词机制
+    #[serde(default)]
+    pub vocabulary: Vec<HotWord>,
+
+    /// 文本替换规则，按顺序作用于识别结果
+    #[serde(default)]
+    pub replace_rules: Vec<ReplaceRule>,
+
+    /// 语音指令配置
+    #[serde(default)]
+    pub voice_commands: VoiceCommandConfig,
+
+    /// 大模型润色配置
+    #[serde(default)]
+    pub post_edit: PostEditConfig,
+
+    /// 文本规整配置（数字、日期等转为书面形式）
+    #[serde(default)]
+    pub itn: ItnConfig,
+
+    /// 标点风格配置，统一各服务商的输出
+    #[serde(default)]
+    pub punctuation: PunctuationConfig,
+
+    /// 翻译模式配置
+    #[serde(default)]
+    pub translation: TranslationConfig,
+
+    /// 输出方式配置
+    #[serde(default)]
+    pub output: OutputConfig,
+
+    /// 会议转写配置
+    #[serde(default)]
+    pub meeting: MeetingConfig,
+
+    /// 本地控制接口配置
+    #[serde(default)]
+    pub api: ApiConfig,
+
+    /// 配置方案
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+
+    /// 当前配置方案名称（空表示只使用全局配置）
+    #[serde(default)]
+    pub active_profile: String,
+
+    /// 引用无法读取的密钥（名称见 secrets_mut），内存中为 None，保存时原样写回引用
+    #[serde(skip)]
+    pub unresolved_secrets: BTreeSet<String>,
+}
+
+/// 配置方案：覆盖部分全局配置，未设置（None）的项沿用全局配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Profile {
+    pub name: String,
+
+    /// 前台应用（进程名，如 code.exe）匹配时自动使用该方案（仅 Windows，其他平台无法获取前台应用）
+    #[serde(default)]
+    pub apps: Vec<String>,
+
+    #[serde(default)]
+    pub provider: Option<String>,
+
+    #[serde(default)]
+    pub language: Option<AsrLanguage>,
+
+    #[serde(default)]
+    pub vocabulary: Option<Vec<HotWord>>,
+
+    #[serde(default)]
+    pub replace_rules: Option<Vec<ReplaceRule>>,
+
+    #[serde(default)]
+    pub punctuation: Option<PunctuationConfig>,
+
+    #[serde(default)]
+    pub itn: Option<ItnConfig>,
+
+    /// 大模型润色开关和模板（接口、密钥沿用全局配置）
+    #[serde(default)]
+    pub post_edit: Option<bool>,
+
+    #[serde(default)]
+    pub post_edit_template: Option<PromptTemplate>,
+
+    /// 翻译开关（翻译服务沿用全局配置）
+    #[serde(default)]
+    pub translation: Option<bool>,
+
+    #[serde(default)]
+    pub output: Option<OutputConfig>,
+}
+
+/// 输出方式配置（见 utils::output）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    #[serde(default)]
+    pub mode: OutputMode,
+
+    /// 追加写入的文件（file）
+    #[serde(default)]
+    pub file_path: String,
+
+    /// 接收定稿句子的地址（http）
+    #[serde(default)]
+    pub endpoint: String,
+
+    #[serde(default = "default_output_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            mode: OutputMode::default(),
+            file_path: String::new(),
+            endpoint: String::new(),
+            timeout_ms: default_output_timeout_ms(),
+        }
+    }
+}
+
+/// 输出方式: keystroke（模拟键盘输入）/ clipboard / file / stdout / http
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputMode {
+    #[default]
+    Keystroke,
+    Clipboard,
+    File,
+    Stdout,
+    Http,
+}
+
+/// 本地控制接口（HTTP + WebSocket，仅监听 127.0.0.1）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_api_port")]
+    pub port: u16,
+
+    /// 访问令牌（请求头 Authorization: Bearer 或查询参数 token）
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_api_port(),
+            token: None,
+        }
+    }
+}
+
+/// 会议转写配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MeetingConfig {
+    /// 转写文件保存目录（空为应用数据目录下的 meetings）
+    #[serde(default)]
+    pub output_dir: String,
+
+    /// 请求说话人分离（仅支持的服务商生效）
+    #[serde(default)]
+    pub diarization: bool,
+}
+
+/// 翻译模式配置（定稿的句子翻译后输入）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranslationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_translation_endpoint")]
+    pub endpoint: String,
+
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    /// 源语言（auto 为自动检测）
+    #[serde(default = "default_translation_source")]
+    pub source_language: String,
+
+    #[serde(default = "default_translation_target")]
+    pub target_language: String,
+
+    #[serde(default)]
+    pub output: TranslationOutput,
+
+    #[serde(default = "default_translation_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_translation_endpoint(),
+            api_key: None,
+            source_language: default_translation_source(),
+            target_language: default_translation_target(),
+            output: TranslationOutput::default(),
+            timeout_ms: default_translation_timeout_ms(),
+        }
+    }
+}
+
+/// 译文输出方式: replace（替换原文）/ append（原文后附译文）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TranslationOutput {
+    #[default]
+    Replace,
+    Append,
+}
+
+/// 标点风格配置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PunctuationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default)]
+    pub style: PunctuationStyle,
+
+    /// 中文与英文/数字之间加空格
+    #[serde(default)]
+    pub cjk_latin_spacing: bool,
+
+    #[serde(default)]
+    pub trailing_period: TrailingPeriod,
+
+    /// 英文句首字母大写
+    #[serde(default)]
+    pub capitalize: bool,
+}
+
+/// 标点宽度: keep（保持原样）/ full_width（中文全角）/ half_width（ASCII 半角）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PunctuationStyle {
+    #[default]
+    Keep,
+    FullWidth,
+    HalfWidth,
+}
+
+/// 句末句号: keep / add / remove
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingPeriod {
+    #[default]
+    Keep,
+    Add,
+    Remove,
+}
+
+/// 文本规整配置，各类规则可单独开关
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItnConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_true")]
+    pub numbers: bool,
+
+    #[serde(default = "default_true")]
+    pub dates: bool,
+
+    #[serde(default = "default_true")]
+    pub times: bool,
+
+    #[serde(default = "default_true")]
+    pub percentages: bool,
+
+    #[serde(default = "default_true")]
+    pub currencies: bool,
+
+    #[serde(default = "default_true")]
+    pub units: bool,
+}
+
+impl Default for ItnConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            numbers: true,
+            dates: true,
+            times: true,
+            percentages: true,
+            currencies: true,
+            units: true,
+        }
+    }
+}
+
+/// 大模型润色配置（OpenAI 兼容的 chat/completions 接口，通常为本地 llama.cpp / Ollama）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostEditConfig {
+    #[serde(default)]
+    pub enabled: bool,
+
+    #[serde(default = "default_post_edit_endpoint")]
+    pub endpoint: String,
+
+    #[serde(default)]
+    pub model: String,
+
+    #[serde(default)]
+    pub api_key: Option<String>,
+
+    #[serde(default)]
+    pub template: PromptTemplate,
+
+    /// 翻译模板的目标语言
+    #[serde(default = "default_target_language")]
+    pub target_language: String,
+
+    /// 超时后回退为原始文本
+    #[serde(default = "default_post_edit_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+impl Default for PostEditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_post_edit_endpoint(),
+            model: String::new(),
+            api_key: None,
+            template: PromptTemplate::default(),
+            target_language: default_target_language(),
+            timeout_ms: default_post_edit_timeout_ms(),
+        }
+    }
+}
+
+/// 润色提示词模板
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptTemplate {
+    #[default]
+    CleanUp,
+    Formalize,
+    Translate,
+    Summarize,
+}
+
+/// 语音指令配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceCommandConfig {
+    /// 是否识别语音指令（关闭后按普通文本输入）。默认关闭，避免单独说出"全选""撤销"等词时误触发按键
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// 自定义指令，优先于内置指令
+    #[serde(default)]
+    pub custom: Vec<VoiceCommand>,
+}
+
+impl Default for VoiceCommandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            custom: Vec::new(),
+        }
+    }
+}
+
+/// 语音指令：整句识别结果等于 phrase 时执行 action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceCommand {
+    pub phrase: String,
+    pub action: CommandAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "text", rename_all = "snake_case")]
+pub enum CommandAction {
+    NewLine,
+    NewParagraph,
+    DeleteWord,
+    DeleteSentence,
+    SelectAll,
+    Undo,
+    StopDictation,
+    /// 输入指定文本（如标点）
+    Insert(String),
+}
+
+/// 文本替换规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplaceRule {
+    /// 匹配内容（字面文本或正则）
+    pub pattern: String,
+
+    /// 替换内容（正则模式下支持 $1 等捕获组引用）
+    #[serde(default)]
+    pub replacement: String,
+
+    #[serde(default)]
+    pub regex: bool,
+
+    #[serde(default)]
+    pub case_sensitive: bool,
+
+    /// 仅在这些应用中生效（进程名，如 "code.exe"；为空表示全部应用）
+    ///
+    /// 目前只有 Windows 能获取前台应用，其他平台上设置了应用的规则不会生效
+    #[serde(default)]
+    pub apps: Vec<String>,
+
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// 热词
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HotWord {
+    pub word: String,
+
+    /// 权重 1-100，越大越优先（不支持权重的服务商按权重排列热词，识别后修正时权重高的优先）
+    #[serde(default)]
+    pub weight: Option<u8>,
+}
+
+/// 取消录音配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelConfig {
+    /// 触发取消的按键
+    #[serde(default)]
+    pub key: CancelKey,
+
+    /// 点击窗口外是否取消
+    #[serde(default = "default_true")]
+    pub outside_click: bool,
+
+    /// 取消时是否删除本次已输入的文字
+    #[serde(default)]
+    pub erase_inserted: bool,
+}
+
+impl Default for CancelConfig {
+    fn default() -> Self {
+        Self {
+            key: CancelKey::default(),
+            outside_click: true,
+            erase_inserted: false,
+        }
+    }
+}
+
+/// 取消按键: any（任意非修饰键）/ escape / none
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CancelKey {
+    #[default]
+    Any,
+    Escape,
+    None,
 }
 
 /// ASR 全局配置（包含所有服务商的配置）
@@ -23,6 +514,10 @@ pub struct AsrConfig {
     #[serde(default = "default_asr_provider")]
     pub provider: String,
 
+    /// 识别语言，由各服务商映射为自身参数
+    #[serde(default)]
+    pub language: AsrLanguage,
+
     /// 豆包配置
     #[serde(default)]
     pub doubao: DoubaoConfig,
@@ -40,6 +535,7 @@ impl Default for AsrConfig {
     fn default() -> Self {
         Self {
             provider: default_asr_provider(),
+            language: AsrLanguage::default(),
             doubao: DoubaoConfig::default(),
             xunfei: XunfeiConfig::default(),
             funasr: FunasrConfig::default(),
@@ -47,6 +543,20 @@ impl Default for AsrConfig {
     }
 }
 
+/// 识别语言: mixed（中英混合）/ zh-CN / en-US / yue（粤语）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub enum AsrLanguage {
+    #[default]
+    #[serde(rename = "mixed")]
+    Mixed,
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[serde(rename = "en-US")]
+    EnUs,
+    #[serde(rename = "yue")]
+    Yue,
+}
+
 /// 豆包 ASR 配置
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DoubaoConfig {
@@ -78,6 +588,50 @@ pub struct FunasrConfig {
 
     #[serde(default = "default_funasr_port")]
     pub port: u16,
+
+    #[serde(default)]
+    pub mode: FunasrMode,
+
+    /// 流式分块参数 [回看, 当前, 前瞻]（单位 60ms）
+    #[serde(default = "default_funasr_chunk_size")]
+    pub chunk_size: [u32; 3],
+
+    /// 实时输入中间结果（定稿时按离线结果修正），关闭则只输入定稿结果；中间结果始终推送给控制接口
+    #[serde(default = "default_true")]
+    pub type_partial: bool,
+
+    /// 本地服务进程管理
+    #[serde(default)]
+    pub service: FunasrServiceConfig,
+}
+
+/// FunASR 服务进程配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunasrServiceConfig {
+    /// 随应用启动服务
+    #[serde(default)]
+    pub auto_start: bool,
+
+    /// 一键包目录（作为工作目录，启动命令相对该目录查找）
+    #[serde(default)]
+    pub folder: String,
+
+    #[serde(default = "default_funasr_command")]
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+impl Default for FunasrServiceConfig {
+    fn default() -> Self {
+        Self {
+            auto_start: false,
+            folder: String::new(),
+            command: default_funasr_command(),
+            args: Vec::new(),
+        }
+    }
 }
 
 impl Default for FunasrConfig {
@@ -85,10 +639,26 @@ impl Default for FunasrConfig {
         Self {
             host: default_funasr_host(),
             port: default_funasr_port(),
+            mode: FunasrMode::default(),
+            chunk_size: default_funasr_chunk_size(),
+            type_partial: true,
+            service: FunasrServiceConfig::default(),
         }
     }
 }
 
+/// FunASR 识别模式: 2pass（流式 + 离线修正）/ online（仅流式）/ offline（仅离线）
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub enum FunasrMode {
+    #[default]
+    #[serde(rename = "2pass")]
+    TwoPass,
+    #[serde(rename = "online")]
+    Online,
+    #[serde(rename = "offline")]
+    Offline,
+}
+
 fn default_asr_provider() -> String {
     "doubao".to_string()
 }
@@ -105,6 +675,56 @@ fn default_funasr_port() -> u16 {
     10095
 }
 
+fn default_funasr_chunk_size() -> [u32; 3] {
+    [5, 10, 5]
+}
+
+/// 一键包的启动脚本（Windows 为 start.bat，其他平台为 start.sh）
+fn default_funasr_command() -> String {
+    if cfg!(windows) { "start.bat" } else { "start.sh" }.to_string()
+}
+
+/// HTTP 输出只投递到本地地址，单句超时较短
+fn default_output_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_api_port() -> u16 {
+    17890
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_post_edit_endpoint() -> String {
+    "http://127.0.0.1:11434/v1/chat/completions".to_string()
+}
+
+fn default_target_language() -> String {
+    "English".to_string()
+}
+
+fn default_post_edit_timeout_ms() -> u64 {
+    5000
+}
+
+fn default_translation_timeout_ms() -> u64 {
+    3000
+}
+
+fn default_translation_endpoint() -> String {
+    "http://127.0.0.1:5000/translate".to_string()
+}
+
+fn default_translation_source() -> String {
+    "auto".to_string()
+}
+
+fn default_translation_target() -> String {
+    "en".to_string()
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum Theme {
@@ -128,21 +748,122 @@ impl AppConfig {
 
         let store = app.store("config.json").map_err(|e| e.to_string())?;
 
-        let config: AppConfig = if let Some(value) = store.get("config") {
-            serde_json::from_value(value).map_err(|e| format!("解析配置失败: {}", e))?
-        } else {
-            AppConfig::default()
+        let managed = ManagedDefaults::load();
+        let mut needs_save = false;
+        let mut config: AppConfig = match store.get("config") {
+            Some(mut raw) => {
+                // 旧版本配置先备份再迁移
+                let version = migration::version_of(&raw);
+                if version < migration::CONFIG_VERSION {
+                    if let Err(e) = migration::backup(app, &raw) {
+                        log::warn!("{}", e);
+                    }
+                    migration::migrate(&mut raw, version);
+                    needs_save = true;
+                }
+                // 统一下发的默认配置垫在用户配置之下
+                if let Some(managed) = &managed {
+                    managed.apply(&mut raw);
+                }
+                match serde_json::from_value(raw.clone()) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        log::warn!("解析配置失败: {}，逐段恢复", e);
+                        if let Err(e) = migration::backup(app, &raw) {
+                            log::warn!("{}", e);
+                        }
+                        let (config, dropped) = migration::recover(&raw);
+                        log::warn!("已恢复配置，以下配置项重置为默认值: {:?}", dropped);
+                        needs_save = true;
+                        config
+                    }
+                }
+            }
+            None => match &managed {
+                Some(managed) => {
+                    let mut raw = serde_json::to_value(AppConfig::default()).map_err(|e| e.to_string())?;
+                    managed.apply(&mut raw);
+                    serde_json::from_value(raw).unwrap_or_else(|e| {
+                        log::warn!("默认配置无法解析: {}", e);
+                        AppConfig::default()
+                    })
+                }
+                None => AppConfig::default(),
+            },
         };
 
+        // 旧版本保存的明文迁移到密钥环
+        if config.resolve_secrets(app) {
+            needs_save = true;
+        }
+        if needs_save {
+            if let Err(e) = config.save(app) {
+                log::warn!("保存迁移后的配置失败: {}", e);
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// 解析在应用外修改的配置（严格校验，不做逐段恢复）
+    pub fn parse_external(app: &tauri::AppHandle, mut raw: serde_json::Value) -> Result<Self, ConfigError> {
+        if let Some(managed) = ManagedDefaults::load() {
+            managed.apply(&mut raw);
+        }
+        let mut config = migration::parse(raw)?;
+        config.resolve_secrets(app);
+        validation::validate(&config)?;
         Ok(config)
     }
 
+    /// 引用替换为密钥原文，返回是否含有明文保存的密钥
+    ///
+    /// 读取失败（如密钥环暂时不可用）的密钥记入 unresolved_secrets，保存时保留引用，不会被删除
+    fn resolve_secrets(&mut self, app: &tauri::AppHandle) -> bool {
+        let mut plaintext = false;
+        let mut unresolved = Vec::new();
+        for (name, field) in self.secrets_mut() {
+            match field.clone() {
+                Some(reference) if secrets::is_reference(&reference) => {
+                    *field = secrets::resolve(app, &reference);
+                    if field.is_none() {
+                        log::warn!("读取密钥 {} 失败", name);
+                        unresolved.push(name.to_string());
+                    }
+                }
+                Some(value) if !value.is_empty() => plaintext = true,
+                _ => {}
+            }
+        }
+        self.unresolved_secrets.extend(unresolved);
+        plaintext
+    }
+
     pub fn save(&self, app: &tauri::AppHandle) -> Result<(), String> {
         use tauri_plugin_store::StoreExt;
 
         let store = app.store("config.json").map_err(|e| e.to_string())?;
 
-        let value = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        // 密钥存入密钥环，配置文件只保存引用
+        // 未能读取的密钥写回原引用；只有用户清空的密钥才删除
+        let mut stored = self.clone();
+        stored.version = migration::CONFIG_VERSION;
+        let unresolved = std::mem::take(&mut stored.unresolved_secrets);
+        for (name, field) in stored.secrets_mut() {
+            match field.as_deref().filter(|v| !v.is_empty()) {
+                Some(value) => *field = Some(secrets::store(app, name, value)?),
+                None if unresolved.contains(name) => *field = Some(secrets::reference(name)),
+                None => {
+                    secrets::delete(app, name);
+                    *field = None;
+                }
+            }
+        }
+        let mut value = serde_json::to_value(&stored).map_err(|e| e.to_string())?;
+        // 与统一下发的值相同的项不写入，下发的默认值更新后仍能生效
+        if let Some(managed) = ManagedDefaults::load() {
+            managed.strip(&mut value);
+        }
 
         store.set("config", value);
         store.save().map_err(|e| e.to_string())?;
@@ -150,3 +871,203 @@ impl AppConfig {
         Ok(())
     }
 }
+
+impl AppConfig {
+    /// 敏感字段（名称用作密钥环中的条目名）
+    fn secrets_mut(&mut self) -> [(&'static str, &mut Option<String>); 6] {
+        [
+            ("doubao.api_key", &mut self.asr.doubao.api_key),
+            ("xunfei.api_key", &mut self.asr.xunfei.api_key),
+            ("xunfei.api_secret", &mut self.asr.xunfei.api_secret),
+            ("post_edit.api_key", &mut self.post_edit.api_key),
+            ("translation.api_key", &mut self.translation.api_key),
+            ("api.token", &mut self.api.token),
+        ]
+    }
+
+    /// 返回给界面的副本，已设置（含未能读取）的密钥替换为掩码
+    pub fn masked(&self) -> Self {
+        let mut config = self.clone();
+        let unresolved = std::mem::take(&mut config.unresolved_secrets);
+        for (name, field) in config.secrets_mut() {
+            if field.as_deref().is_some_and(|v| !v.is_empty()) || unresolved.contains(name) {
+                *field = Some(secrets::MASK.to_string());
+            }
+        }
+        config
+    }
+
+    /// 去掉所有密钥的副本（用于导出）
+    pub fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config.unresolved_secrets.clear();
+        for (_, field) in config.secrets_mut() {
+            *field = None;
+        }
+        config
+    }
+
+    /// 导入的配置未包含密钥时沿用当前值
+    pub fn keep_secrets(&mut self, current: &AppConfig) {
+        self.inherit_secrets(current, |v| v.is_empty() || v == secrets::MASK);
+    }
+
+    /// 锁定项恢复为统一下发的值，返回被覆盖的项
+    pub fn enforce_locked(&mut self) -> Vec<String> {
+        let Some(managed) = ManagedDefaults::load() else { return Vec::new() };
+        let Ok(mut raw) = serde_json::to_value(&*self) else { return Vec::new() };
+        let overridden = managed.enforce(&mut raw);
+        if !overridden.is_empty() {
+            match serde_json::from_value(raw) {
+                Ok(config) => *self = config,
+                Err(e) => log::warn!("应用锁定配置失败: {}", e),
+            }
+        }
+        overridden
+    }
+
+    /// 界面提交的掩码表示未修改，恢复为当前值
+    pub fn restore_masked(&mut self, current: &AppConfig) {
+        self.inherit_secrets(current, |v| v == secrets::MASK);
+    }
+
+    /// 未能读取的密钥沿用 current 中已读取的值（重新读取配置文件时密钥环暂时不可用）
+    pub fn fill_unresolved(&mut self, current: &AppConfig) {
+        let mut current = current.clone();
+        let unresolved = self.unresolved_secrets.clone();
+        let mut filled = Vec::new();
+        for ((name, field), (_, old)) in self.secrets_mut().into_iter().zip(current.secrets_mut()) {
+            if unresolved.contains(name) && old.is_some() {
+                *field = old.take();
+                filled.push(name);
+            }
+        }
+        for name in filled {
+            self.unresolved_secrets.remove(name);
+        }
+    }
+
+    /// 满足 unchanged 的密钥（None 视为空字符串）沿用 current 的值，连同未能读取的标记
+    fn inherit_secrets(&mut self, current: &AppConfig, unchanged: impl Fn(&str) -> bool) {
+        let mut current = current.clone();
+        let mut inherited = Vec::new();
+        for ((name, field), (_, old)) in self.secrets_mut().into_iter().zip(current.secrets_mut()) {
+            if unchanged(field.as_deref().unwrap_or_default()) {
+                *field = old.take();
+                inherited.push(name);
+            }
+        }
+        for name in inherited {
+            if current.unresolved_secrets.contains(name) {
+                self.unresolved_secrets.insert(name.to_string());
+            }
+        }
+    }
+}
+
+impl AppConfig {
+    /// 本次会话使用的方案：前台应用匹配的方案优先，其次为当前方案
+    pub fn profile_for(&self, app: Option<&str>) -> Option<&Profile> {
+        let by_app = app.and_then(|a| {
+            self.profiles.iter().find(|p| p.apps.iter().any(|x| x.eq_ignore_ascii_case(a)))
+        });
+        by_app.or_else(|| self.profiles.iter().find(|p| p.name == self.active_profile))
+    }
+
+    /// 应用方案覆盖后的配置
+    pub fn effective(&self, app: Option<&str>) -> AppConfig {
+        let mut config = self.clone();
+        let Some(profile) = self.profile_for(app) else { return config };
+        log::info!("使用配置方案: {}", profile.name);
+
+        if let Some(provider) = &profile.provider {
+            config.asr.provider = provider.clone();
+        }
+        if let Some(language) = &profile.language {
+            config.asr.language = language.clone();
+        }
+        if let Some(vocabulary) = &profile.vocabulary {
+            config.vocabulary = vocabulary.clone();
+        }
+        if let Some(rules) = &profile.replace_rules {
+            config.replace_rules = rules.clone();
+        }
+        if let Some(punctuation) = &profile.punctuation {
+            config.punctuation = punctuation.clone();
+        }
+        if let Some(itn) = &profile.itn {
+            config.itn = itn.clone();
+        }
+        if let Some(enabled) = profile.post_edit {
+            config.post_edit.enabled = enabled;
+        }
+        if let Some(template) = &profile.post_edit_template {
+            config.post_edit.template = template.clone();
+        }
+        if let Some(enabled) = profile.translation {
+            config.translation.enabled = enabled;
+        }
+        if let Some(output) = &profile.output {
+            config.output = output.clone();
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_secrets(doubao: Option<&str>, xunfei: Option<&str>, post_edit: Option<&str>) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.asr.doubao.api_key = doubao.map(String::from);
+        config.asr.xunfei.api_key = xunfei.map(String::from);
+        config.post_edit.api_key = post_edit.map(String::from);
+        config
+    }
+
+    #[test]
+    fn keep_secrets_fills_missing_and_masked_values() {
+        let mut current = with_secrets(Some("old-doubao"), None, Some("old-llm"));
+        current.unresolved_secrets.insert("xunfei.api_key".to_string());
+
+        let mut imported = with_secrets(None, Some(secrets::MASK), Some("new-llm"));
+        imported.keep_secrets(&current);
+        assert_eq!(imported.asr.doubao.api_key.as_deref(), Some("old-doubao"));
+        assert_eq!(imported.asr.xunfei.api_key, None);
+        assert_eq!(imported.post_edit.api_key.as_deref(), Some("new-llm"));
+        assert_eq!(imported.unresolved_secrets, BTreeSet::from(["xunfei.api_key".to_string()]));
+    }
+
+    #[test]
+    fn restore_masked_keeps_explicit_clears() {
+        let mut current = with_secrets(Some("old-doubao"), None, Some("old-llm"));
+        current.unresolved_secrets.insert("xunfei.api_key".to_string());
+
+        // 界面收到的是掩码
+        let masked = current.masked();
+        assert_eq!(masked.asr.xunfei.api_key.as_deref(), Some(secrets::MASK));
+
+        let mut submitted = with_secrets(Some(secrets::MASK), Some(secrets::MASK), Some(""));
+        submitted.restore_masked(&current);
+        assert_eq!(submitted.asr.doubao.api_key.as_deref(), Some("old-doubao"));
+        assert_eq!(submitted.asr.xunfei.api_key, None);
+        assert!(submitted.unresolved_secrets.contains("xunfei.api_key"));
+        // 清空的密钥不沿用旧值
+        assert_eq!(submitted.post_edit.api_key.as_deref(), Some(""));
+    }
+
+    #[test]
+    fn fill_unresolved_uses_current_values() {
+        let current = with_secrets(Some("old-doubao"), None, None);
+
+        let mut reloaded = with_secrets(None, None, Some("new-llm"));
+        reloaded.unresolved_secrets.insert("doubao.api_key".to_string());
+        reloaded.unresolved_secrets.insert("xunfei.api_key".to_string());
+        reloaded.fill_unresolved(&current);
+        assert_eq!(reloaded.asr.doubao.api_key.as_deref(), Some("old-doubao"));
+        assert_eq!(reloaded.post_edit.api_key.as_deref(), Some("new-llm"));
+        // 当前也没有的密钥保留标记，保存时写回引用
+        assert_eq!(reloaded.unresolved_secrets, BTreeSet::from(["xunfei.api_key".to_string()]));
+    }
+}
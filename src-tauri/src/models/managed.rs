@@ -0,0 +1,198 @@
+//! 统一下发的默认配置
+//!
+//! 系统级只读文件 defaults.json（/etc/voicekit、%ProgramData%\VoiceKit 或程序所在目录），格式:
+//! { "config": { ...部分配置... }, "locked": ["asr.provider", ...] }
+//! config 作为用户配置之下的一层，用户未设置的项使用它；locked 中的项始终使用下发的值。
+//! 用户配置文件只保存与下发值不同的项，下发的默认值更新后对未修改过的项生效
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+
+const FILE_NAME: &str = "defaults.json";
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct ManagedDefaults {
+    #[serde(default)]
+    pub config: Value,
+
+    /// 锁定的配置项（点分路径）
+    #[serde(default)]
+    pub locked: Vec<String>,
+}
+
+/// 按优先级排列的默认配置文件位置
+fn candidates() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    #[cfg(unix)]
+    paths.push(PathBuf::from("/etc/voicekit").join(FILE_NAME));
+    #[cfg(windows)]
+    if let Ok(dir) = std::env::var("ProgramData") {
+        paths.push(PathBuf::from(dir).join("VoiceKit").join(FILE_NAME));
+    }
+    if let Some(dir) = std::env::current_exe().ok().and_then(|p| p.parent().map(|d| d.to_path_buf())) {
+        paths.push(dir.join(FILE_NAME));
+    }
+    paths
+}
+
+impl ManagedDefaults {
+    /// 读取第一个存在的默认配置文件（无效文件忽略并记录日志）
+    pub fn load() -> Option<Self> {
+        let path = candidates().into_iter().find(|p| p.is_file())?;
+        let content = std::fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<Self>(&content) {
+            Ok(defaults) => Some(defaults),
+            Err(e) => {
+                log::warn!("默认配置 {} 无效: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// 把默认配置合入用户配置之下，并强制锁定项，返回被覆盖的锁定项
+    pub fn apply(&self, user: &mut Value) -> Vec<String> {
+        let mut merged = self.config.clone();
+        merge(&mut merged, user.take());
+        *user = merged;
+        self.enforce(user)
+    }
+
+    /// 锁定项恢复为下发的值，返回被覆盖的项
+    pub fn enforce(&self, config: &mut Value) -> Vec<String> {
+        let mut overridden = Vec::new();
+        for key in &self.locked {
+            let path: Vec<&str> = key.split('.').collect();
+            let Some(value) = get(&self.config, &path) else { continue };
+            if get(config, &path) != Some(value) {
+                set(config, &path, value.clone());
+                overridden.push(key.clone());
+            }
+        }
+        overridden
+    }
+
+    /// 去掉与下发值相同的项，返回只含用户修改部分的配置（保存前调用）
+    pub fn strip(&self, config: &mut Value) {
+        strip_same(config, &self.config);
+    }
+}
+
+/// 深度合并：overlay 中的值覆盖 base（对象逐字段合并）
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// 删除 value 中与 base 相同的项（对象逐字段比较，比较后为空的对象一并删除）
+fn strip_same(value: &mut Value, base: &Value) {
+    let (Value::Object(fields), Value::Object(base)) = (value, base) else { return };
+    fields.retain(|key, field| match base.get(key) {
+        Some(b) if b == field => false,
+        Some(b) if field.is_object() && b.is_object() => {
+            strip_same(field, b);
+            field.as_object().is_some_and(|f| !f.is_empty())
+        }
+        _ => true,
+    });
+}
+
+fn get<'a>(value: &'a Value, path: &[&str]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, key| v.get(*key))
+}
+
+fn set(value: &mut Value, path: &[&str], new: Value) {
+    let Some((last, parents)) = path.split_last() else { return };
+    let mut current = value;
+    for key in parents {
+        if !current.is_object() {
+            *current = Value::Object(Map::new());
+        }
+        current = current.as_object_mut().unwrap().entry(key.to_string()).or_insert_with(|| Value::Object(Map::new()));
+    }
+    if !current.is_object() {
+        *current = Value::Object(Map::new());
+    }
+    current.as_object_mut().unwrap().insert(last.to_string(), new);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn managed(config: Value, locked: &[&str]) -> ManagedDefaults {
+        ManagedDefaults { config, locked: locked.iter().map(|s| s.to_string()).collect() }
+    }
+
+    #[test]
+    fn merge_overlays_nested_objects() {
+        let mut base = json!({ "asr": { "provider": "funasr", "funasr": { "host": "a", "port": 1 } }, "theme": "google" });
+        merge(&mut base, json!({ "asr": { "funasr": { "port": 2 } }, "theme": "default", "vocabulary": [] }));
+        assert_eq!(
+            base,
+            json!({ "asr": { "provider": "funasr", "funasr": { "host": "a", "port": 2 } }, "theme": "default", "vocabulary": [] })
+        );
+
+        // 类型不同时整体替换
+        let mut base = json!({ "output": { "mode": "file" } });
+        merge(&mut base, json!({ "output": "file" }));
+        assert_eq!(base, json!({ "output": "file" }));
+    }
+
+    #[test]
+    fn set_creates_and_replaces_parents() {
+        let mut value = json!({ "asr": "broken" });
+        set(&mut value, &["asr", "funasr", "host"], json!("h"));
+        assert_eq!(value, json!({ "asr": { "funasr": { "host": "h" } } }));
+
+        let mut value = json!(null);
+        set(&mut value, &["api", "port"], json!(1));
+        assert_eq!(value, json!({ "api": { "port": 1 } }));
+
+        let mut value = json!({ "a": 1 });
+        set(&mut value, &[], json!(2));
+        assert_eq!(value, json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn enforce_restores_locked_paths() {
+        let defaults = managed(json!({ "asr": { "provider": "funasr", "funasr": { "host": "h" } } }), &[
+            "asr.provider",
+            "asr.funasr.host",
+            "theme",
+        ]);
+        let mut config = json!({ "asr": { "provider": "doubao", "funasr": 1 }, "theme": "google" });
+        let overridden = defaults.enforce(&mut config);
+        assert_eq!(overridden, ["asr.provider", "asr.funasr.host"]);
+        // 未下发值的锁定项不处理
+        assert_eq!(config, json!({ "asr": { "provider": "funasr", "funasr": { "host": "h" } }, "theme": "google" }));
+        assert!(defaults.enforce(&mut config).is_empty());
+    }
+
+    #[test]
+    fn apply_then_strip_round_trips_user_changes() {
+        let defaults = managed(json!({ "asr": { "provider": "funasr", "funasr": { "host": "h", "port": 1 } }, "auto_start": true }), &["asr.provider"]);
+        let user = json!({ "asr": { "provider": "doubao", "funasr": { "port": 2 } }, "theme": "google" });
+        let mut config = user.clone();
+        defaults.apply(&mut config);
+        assert_eq!(
+            config,
+            json!({ "asr": { "provider": "funasr", "funasr": { "host": "h", "port": 2 } }, "auto_start": true, "theme": "google" })
+        );
+
+        defaults.strip(&mut config);
+        assert_eq!(config, json!({ "asr": { "funasr": { "port": 2 } }, "theme": "google" }));
+    }
+}
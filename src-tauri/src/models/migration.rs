@@ -0,0 +1,189 @@
+//! 配置版本迁移
+//!
+//! 配置文件带 version 字段，读取时按顺序执行旧版本到当前版本的迁移（迁移前备份原文件）；
+//! 整体解析失败时逐段恢复，保留能解析的部分，无效的部分使用默认值
+
+use super::config::AppConfig;
+use serde_json::{Map, Value};
+use tauri::Manager;
+
+/// 当前配置版本
+pub const CONFIG_VERSION: u32 = 1;
+
+/// 迁移步骤，MIGRATIONS[n] 把版本 n 迁移到 n + 1
+const MIGRATIONS: &[fn(&mut Map<String, Value>)] = &[v0_to_v1];
+
+/// 版本 0（无 version 字段）：豆包配置平铺在 asr 下，access_key 即 api_key，主题可能为 system
+fn v0_to_v1(config: &mut Map<String, Value>) {
+    let legacy_key = config.remove("api_key");
+    if let Some(Value::Object(asr)) = config.get_mut("asr") {
+        let mut doubao = match asr.remove("doubao") {
+            Some(Value::Object(d)) => d,
+            _ => Map::new(),
+        };
+        if let Some(app_id) = asr.remove("app_id") {
+            doubao.entry("app_id").or_insert(app_id);
+        }
+        if let Some(key) = asr.remove("access_key").or(legacy_key) {
+            doubao.entry("api_key").or_insert(key);
+        }
+        for obsolete in ["resource_id", "ws_url"] {
+            asr.remove(obsolete);
+        }
+        asr.insert("doubao".to_string(), Value::Object(doubao));
+    }
+    if config.get("theme").and_then(Value::as_str) == Some("system") {
+        config.insert("theme".to_string(), "default".into());
+    }
+}
+
+pub fn version_of(raw: &Value) -> u32 {
+    raw.get("version").and_then(Value::as_u64).unwrap_or(0) as u32
+}
+
+/// 从 from 版本迁移到当前版本
+pub fn migrate(raw: &mut Value, from: u32) {
+    let Some(config) = raw.as_object_mut() else { return };
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        log::info!("配置迁移: v{} -> v{}", version, version + 1);
+        step(config);
+    }
+    config.insert("version".to_string(), CONFIG_VERSION.into());
+}
+
+/// 严格解析外部配置（如导入的文件）：迁移到当前版本，任何字段无效都报错
+pub fn parse(mut raw: Value) -> Result<AppConfig, String> {
+    if !raw.is_object() {
+        return Err("配置格式错误: 应为 JSON 对象".to_string());
+    }
+    let version = version_of(&raw);
+    if version > CONFIG_VERSION {
+        return Err(format!("配置版本 v{} 高于当前支持的 v{}", version, CONFIG_VERSION));
+    }
+    migrate(&mut raw, version);
+    serde_json::from_value(raw).map_err(|e| format!("配置格式错误: {}", e))
+}
+
+/// 迁移或恢复前备份原配置（写入 config.v{版本}.bak.json）
+pub fn backup(app: &tauri::AppHandle, raw: &Value) -> Result<(), String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("config.v{}.bak.json", version_of(raw)));
+    let content = serde_json::to_string_pretty(raw).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("备份配置失败: {}", e))?;
+    log::info!("已备份配置: {}", path.display());
+    Ok(())
+}
+
+/// 逐段恢复：依次把原配置的各字段合入默认配置，合入后无法解析的字段（对象则深入下一层）丢弃，
+/// 返回恢复的配置和被丢弃的字段路径
+pub fn recover(raw: &Value) -> (AppConfig, Vec<String>) {
+    let mut doc = serde_json::to_value(default_config()).unwrap_or_default();
+    let mut dropped = Vec::new();
+    merge_valid(&mut doc, raw, &mut Vec::new(), &mut dropped);
+    let config = serde_json::from_value(doc).unwrap_or_else(|_| default_config());
+    (config, dropped)
+}
+
+/// 带 serde 默认值的配置（与读取空配置文件一致）
+fn default_config() -> AppConfig {
+    serde_json::from_value(Value::Object(Map::new())).unwrap_or_default()
+}
+
+fn merge_valid(doc: &mut Value, raw: &Value, path: &mut Vec<String>, dropped: &mut Vec<String>) {
+    let Some(fields) = raw.as_object() else { return };
+    for (key, value) in fields {
+        let Some(parent) = object_at(doc, path) else { return };
+        let old = parent.insert(key.clone(), value.clone());
+        path.push(key.clone());
+
+        if serde_json::from_value::<AppConfig>(doc.clone()).is_err() {
+            let parent = object_at(doc, &path[..path.len() - 1]).unwrap();
+            match old {
+                Some(old) => parent.insert(key.clone(), old),
+                None => parent.remove(key),
+            };
+            let nested = value.is_object() && object_at(doc, path).is_some();
+            if nested {
+                merge_valid(doc, value, path, dropped);
+            } else {
+                log::warn!("配置项 {} 无效，使用默认值", path.join("."));
+                dropped.push(path.join("."));
+            }
+        }
+        path.pop();
+    }
+}
+
+fn object_at<'a>(doc: &'a mut Value, path: &[String]) -> Option<&'a mut Map<String, Value>> {
+    path.iter()
+        .try_fold(doc, |v, key| v.get_mut(key))
+        .and_then(Value::as_object_mut)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::Theme;
+    use serde_json::json;
+
+    fn fixture(content: &str) -> Value {
+        serde_json::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn v0_to_v1_moves_legacy_doubao_fields() {
+        let mut raw = fixture(include_str!("../../tests/fixtures/config.v0.json"));
+        migrate(&mut raw, 0);
+        assert_eq!(raw["version"], CONFIG_VERSION);
+        assert_eq!(raw["theme"], "default");
+        assert!(raw.get("api_key").is_none());
+        assert_eq!(
+            raw["asr"],
+            json!({
+                "provider": "doubao",
+                "doubao": { "app_id": "your-app-id-here", "api_key": "your-access-key-here" }
+            })
+        );
+    }
+
+    #[test]
+    fn v0_to_v1_falls_back_to_top_level_key() {
+        let mut raw = json!({ "api_key": "legacy", "asr": { "doubao": { "app_id": "kept" }, "app_id": "ignored" } });
+        migrate(&mut raw, 0);
+        assert_eq!(raw["asr"]["doubao"], json!({ "app_id": "kept", "api_key": "legacy" }));
+    }
+
+    #[test]
+    fn parse_migrates_legacy_and_current_examples() {
+        let config = parse(fixture(include_str!("../../tests/fixtures/config.v0.json"))).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.theme, Theme::Default);
+        assert_eq!(config.asr.doubao.app_id.as_deref(), Some("your-app-id-here"));
+
+        let example = fixture(include_str!("../../config.example.json"));
+        let config = parse(example["config"].clone()).unwrap();
+        assert_eq!(config.asr.doubao.api_key.as_deref(), Some("your-access-key-here"));
+    }
+
+    #[test]
+    fn parse_rejects_future_and_invalid_documents() {
+        let err = parse(fixture(include_str!("../../tests/fixtures/config.future.json"))).unwrap_err();
+        assert!(err.contains("v99"), "{}", err);
+        assert!(parse(json!([])).is_err());
+        assert!(parse(fixture(include_str!("../../tests/fixtures/config.partial.json"))).is_err());
+    }
+
+    #[test]
+    fn recover_keeps_valid_fields() {
+        let (config, mut dropped) = recover(&fixture(include_str!("../../tests/fixtures/config.partial.json")));
+        dropped.sort();
+        assert_eq!(dropped, ["asr.funasr.port", "theme", "vocabulary"]);
+        assert_eq!(config.shortcut, "Ctrl+K");
+        assert_eq!(config.theme, Theme::Default);
+        assert!(config.vocabulary.is_empty());
+        assert_eq!(config.asr.provider, "xunfei");
+        assert_eq!(config.asr.funasr.host, "192.168.1.10");
+        assert_eq!(config.asr.funasr.port, default_config().asr.funasr.port);
+    }
+}
@@ -1,3 +1,6 @@
 pub mod config;
 pub mod state;
 pub mod buffer;
+pub mod migration;
+pub mod managed;
+pub mod validation;
@@ -1,4 +1,4 @@
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicUsize, Ordering}};
 use crate::models::config::AppConfig;
 use crate::models::buffer::{AudioBuffer, TextBuffer};
 use crate::asr::manager::AsrManager;
@@ -10,6 +10,10 @@ pub struct AppState {
     pub text_buffer: Arc<TextBuffer>,        // 文本缓冲区：识别结果暂存
     pub asr_manager: Arc<AsrManager>,        // ASR管理器：语音识别服务协调
     pub is_simulating_input: AtomicBool,     // 输入模拟标志：是否正在模拟键盘输入
+    pub is_cancelled: Arc<AtomicBool>,       // 取消标志：本次录音被取消，丢弃识别结果
+    pub inserted_chars: Arc<AtomicUsize>,    // 本次会话已输入的字符数（扣除退格）
+    pub dictation_window: Arc<Mutex<Option<isize>>>, // 本次会话开始时的前台窗口
+    pub is_meeting: Arc<AtomicBool>,         // 会议模式：本次录音为会议转写，不输入文字
 }
 
 impl Clone for AppState {
@@ -21,6 +25,10 @@ impl Clone for AppState {
             text_buffer: self.text_buffer.clone(),
             asr_manager: self.asr_manager.clone(),
             is_simulating_input: AtomicBool::new(self.is_simulating_input.load(Ordering::SeqCst)),
+            is_cancelled: self.is_cancelled.clone(),
+            inserted_chars: self.inserted_chars.clone(),
+            dictation_window: self.dictation_window.clone(),
+            is_meeting: self.is_meeting.clone(),
         }
     }
 }
@@ -39,6 +47,10 @@ impl AppState {
             text_buffer,
             asr_manager,
             is_simulating_input: AtomicBool::new(false),
+            is_cancelled: Arc::new(AtomicBool::new(false)),
+            inserted_chars: Arc::new(AtomicUsize::new(0)),
+            dictation_window: Arc::new(Mutex::new(None)),
+            is_meeting: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -0,0 +1,294 @@
+//! 配置校验
+//!
+//! 保存前检查整份配置，错误按字段路径（如 asr.funasr.port、replace_rules.0.pattern）返回给界面。
+//! 服务商凭据允许暂不填写（可以先保存其他设置），开始识别时由 check_credentials 检查，
+//! 失败时通过系统通知告知用户
+
+use super::config::{AppConfig, OutputMode};
+use crate::utils::shortcut::parse_shortcut;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+const PROVIDERS: &[&str] = &["doubao", "xunfei", "funasr"];
+const MIN_TOKEN_LEN: usize = 16;
+const TIMEOUT_RANGE: std::ops::RangeInclusive<u64> = 100..=60_000;
+
+/// 配置错误：message 为概要，fields 为各字段的错误（字段路径 -> 说明）
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ConfigError {
+    pub message: String,
+    pub fields: BTreeMap<String, String>,
+}
+
+impl From<String> for ConfigError {
+    fn from(message: String) -> Self {
+        Self { message, fields: BTreeMap::new() }
+    }
+}
+
+impl ConfigError {
+    pub fn field(key: &str, message: String) -> Self {
+        Self { message: message.clone(), fields: BTreeMap::from([(key.to_string(), message)]) }
+    }
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)?;
+        for (key, message) in &self.fields {
+            write!(f, "\n{}: {}", key, message)?;
+        }
+        Ok(())
+    }
+}
+
+/// 校验整份配置（密钥须为原文，即已恢复掩码）
+pub fn validate(config: &AppConfig) -> Result<(), ConfigError> {
+    let mut errors = BTreeMap::new();
+    let mut error = |key: String, message: String| {
+        errors.entry(key).or_insert(message);
+    };
+
+    // 快捷键：主快捷键必填，附加快捷键可为空，彼此不能重复
+    let mut shortcuts = Vec::new();
+    for (key, value, required) in [
+        ("shortcut", &config.shortcut, true),
+        ("undo_shortcut", &config.undo_shortcut, false),
+        ("language_shortcut", &config.language_shortcut, false),
+        ("profile_shortcut", &config.profile_shortcut, false),
+    ] {
+        if value.trim().is_empty() {
+            if required {
+                error(key.into(), "请设置快捷键".into());
+            }
+            continue;
+        }
+        match parse_shortcut(value) {
+            Ok(sc) => match shortcuts.iter().find(|(_, other)| *other == sc) {
+                Some((other, _)) => error(key.into(), format!("与 {} 重复", other)),
+                None => shortcuts.push((key, sc)),
+            },
+            Err(e) => error(key.into(), e),
+        }
+    }
+
+    // 服务商：全局和各方案用到的服务商须为已知服务商
+    let mut used = vec![("asr.provider".to_string(), config.asr.provider.as_str())];
+    for (i, p) in config.profiles.iter().enumerate() {
+        if let Some(provider) = &p.provider {
+            used.push((format!("profiles.{}.provider", i), provider.as_str()));
+        }
+    }
+    for (key, provider) in &used {
+        if !PROVIDERS.contains(provider) {
+            error(key.clone(), format!("未知的服务商: {}", provider));
+        }
+    }
+    let uses = |name: &str| used.iter().any(|(_, p)| *p == name);
+    let funasr = &config.asr.funasr;
+    if uses("funasr") || funasr.service.auto_start {
+        let host = funasr.host.trim();
+        if host.is_empty() || host.contains("://") || host.contains(char::is_whitespace) {
+            error("asr.funasr.host".into(), "请填写主机名或 IP（不含协议）".into());
+        }
+        if funasr.port == 0 {
+            error("asr.funasr.port".into(), "端口应为 1-65535".into());
+        }
+        if funasr.chunk_size.iter().any(|&n| n > 100) || funasr.chunk_size[1] == 0 {
+            error("asr.funasr.chunk_size".into(), "分块参数应为 0-100，且当前块大于 0".into());
+        }
+    }
+    if funasr.service.auto_start {
+        let folder = funasr.service.folder.trim();
+        if folder.is_empty() || !std::path::Path::new(folder).is_dir() {
+            error("asr.funasr.service.folder".into(), "目录不存在".into());
+        }
+        if funasr.service.command.trim().is_empty() {
+            error("asr.funasr.service.command".into(), "请填写启动命令".into());
+        }
+    }
+
+    // 润色和翻译接口
+    let post_edit = &config.post_edit;
+    let translation = &config.translation;
+    let uses_post_edit = post_edit.enabled || config.profiles.iter().any(|p| p.post_edit == Some(true));
+    let uses_translation = translation.enabled || config.profiles.iter().any(|p| p.translation == Some(true));
+    for (prefix, enabled, endpoint, timeout_ms) in [
+        ("post_edit", uses_post_edit, &post_edit.endpoint, post_edit.timeout_ms),
+        ("translation", uses_translation, &translation.endpoint, translation.timeout_ms),
+    ] {
+        if !enabled {
+            continue;
+        }
+        if !is_http_url(endpoint) {
+            error(format!("{}.endpoint", prefix), "请填写 http(s) 地址".into());
+        }
+        if !TIMEOUT_RANGE.contains(&timeout_ms) {
+            error(format!("{}.timeout_ms", prefix), format!("超时应为 {}-{} 毫秒", TIMEOUT_RANGE.start(), TIMEOUT_RANGE.end()));
+        }
+    }
+    if uses_translation && translation.target_language.trim().is_empty() {
+        error("translation.target_language".into(), "请填写目标语言".into());
+    }
+
+    // 列表项
+    for (i, word) in config.vocabulary.iter().enumerate() {
+        if word.word.trim().is_empty() {
+            error(format!("vocabulary.{}.word", i), "热词不能为空".into());
+        }
+        if word.weight.is_some_and(|w| !(1..=100).contains(&w)) {
+            error(format!("vocabulary.{}.weight", i), "权重应为 1-100".into());
+        }
+    }
+    for (i, rule) in config.replace_rules.iter().enumerate() {
+        if rule.pattern.is_empty() {
+            error(format!("replace_rules.{}.pattern", i), "匹配内容不能为空".into());
+        } else if rule.regex {
+            let compiled = regex::RegexBuilder::new(&rule.pattern)
+                .case_insensitive(!rule.case_sensitive)
+                .build();
+            if let Err(e) = compiled {
+                error(format!("replace_rules.{}.pattern", i), format!("正则表达式无效: {}", e));
+            }
+        }
+    }
+    for (i, command) in config.voice_commands.custom.iter().enumerate() {
+        if command.phrase.trim().is_empty() {
+            error(format!("voice_commands.custom.{}.phrase", i), "指令不能为空".into());
+        }
+    }
+
+    // 配置方案
+    for (i, profile) in config.profiles.iter().enumerate() {
+        if profile.name.trim().is_empty() {
+            error(format!("profiles.{}.name", i), "方案名称不能为空".into());
+        } else if config.profiles[..i].iter().any(|p| p.name == profile.name) {
+            error(format!("profiles.{}.name", i), format!("方案名称重复: {}", profile.name));
+        }
+    }
+    if !config.active_profile.is_empty() && !config.profiles.iter().any(|p| p.name == config.active_profile) {
+        error("active_profile".into(), format!("配置方案不存在: {}", config.active_profile));
+    }
+
+    // 输出方式（全局和各方案）
+    let outputs = std::iter::once(("output".to_string(), &config.output)).chain(
+        config.profiles.iter().enumerate()
+            .filter_map(|(i, p)| p.output.as_ref().map(|o| (format!("profiles.{}.output", i), o))),
+    );
+    for (prefix, output) in outputs {
+        match output.mode {
+            OutputMode::File => {
+                let path = output.file_path.trim();
+                if path.is_empty() || !std::path::Path::new(path).is_absolute() {
+                    error(format!("{}.file_path", prefix), "请填写绝对路径".into());
+                }
+            }
+            OutputMode::Http => {
+                if !is_http_url(&output.endpoint) {
+                    error(format!("{}.endpoint", prefix), "请填写 http(s) 地址".into());
+                }
+                if !TIMEOUT_RANGE.contains(&output.timeout_ms) {
+                    error(format!("{}.timeout_ms", prefix), format!("超时应为 {}-{} 毫秒", TIMEOUT_RANGE.start(), TIMEOUT_RANGE.end()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if config.api.enabled {
+        if config.api.port == 0 {
+            error("api.port".into(), "端口应为 1-65535".into());
+        }
+        // 未能读取的令牌保存时写回原引用，不检查长度
+        let unresolved = config.unresolved_secrets.contains("api.token");
+        if !unresolved && config.api.token.as_deref().map_or(0, |t| t.trim().len()) < MIN_TOKEN_LEN {
+            error("api.token".into(), format!("访问令牌至少 {} 个字符", MIN_TOKEN_LEN));
+        }
+    }
+
+    let output_dir = config.meeting.output_dir.trim();
+    if !output_dir.is_empty() && !std::path::Path::new(output_dir).is_absolute() {
+        error("meeting.output_dir".into(), "请填写绝对路径".into());
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError { message: format!("配置有 {} 处错误", errors.len()), fields: errors })
+    }
+}
+
+/// 检查本次会话使用的服务商凭据（config 为已应用方案的配置，开始识别时调用）
+pub fn check_credentials(config: &AppConfig) -> Result<(), ConfigError> {
+    let (doubao, xunfei) = (&config.asr.doubao, &config.asr.xunfei);
+    let required = match config.asr.provider.as_str() {
+        "doubao" => vec![
+            ("asr.doubao.app_id", &doubao.app_id, "请填写 App ID"),
+            ("asr.doubao.api_key", &doubao.api_key, "请填写 Access Key"),
+        ],
+        "xunfei" => vec![
+            ("asr.xunfei.app_id", &xunfei.app_id, "请填写 APPID"),
+            ("asr.xunfei.api_key", &xunfei.api_key, "请填写 APIKey"),
+            ("asr.xunfei.api_secret", &xunfei.api_secret, "请填写 APISecret"),
+        ],
+        _ => Vec::new(),
+    };
+
+    let mut errors = BTreeMap::new();
+    for (key, value, message) in required {
+        // 密钥名称即去掉 asr. 前缀的字段路径
+        if config.unresolved_secrets.contains(key.trim_start_matches("asr.")) {
+            errors.insert(key.to_string(), "无法读取已保存的密钥，请重新填写".to_string());
+        } else if blank(value) {
+            errors.insert(key.to_string(), message.to_string());
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError { message: format!("{} 的凭据不完整", config.asr.provider), fields: errors })
+    }
+}
+
+fn blank(value: &Option<String>) -> bool {
+    value.as_deref().is_none_or(|v| v.trim().is_empty())
+}
+
+fn is_http_url(value: &str) -> bool {
+    reqwest::Url::parse(value.trim()).is_ok_and(|u| matches!(u.scheme(), "http" | "https"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_length_skips_unresolved_secret() {
+        let mut config = AppConfig::default();
+        config.api.enabled = true;
+        config.api.token = Some("short".to_string());
+        let error = validate(&config).unwrap_err();
+        assert_eq!(error.fields.keys().collect::<Vec<_>>(), ["api.token"]);
+
+        // 密钥环不可用时令牌为空，不应阻止保存
+        config.api.token = None;
+        config.unresolved_secrets.insert("api.token".to_string());
+        assert!(validate(&config).is_ok());
+    }
+
+    #[test]
+    fn credentials_of_active_provider() {
+        let mut config = AppConfig::default();
+        config.asr.provider = "xunfei".to_string();
+        config.asr.xunfei.app_id = Some("app".to_string());
+        config.asr.xunfei.api_key = Some(" ".to_string());
+        config.unresolved_secrets.insert("xunfei.api_secret".to_string());
+        let error = check_credentials(&config).unwrap_err();
+        assert_eq!(error.fields["asr.xunfei.api_key"], "请填写 APIKey");
+        assert_eq!(error.fields["asr.xunfei.api_secret"], "无法读取已保存的密钥，请重新填写");
+        assert!(!error.fields.contains_key("asr.xunfei.app_id"));
+
+        config.asr.provider = "funasr".to_string();
+        assert!(check_credentials(&config).is_ok());
+    }
+}
@@ -1,27 +1,69 @@
 use tauri::tray::{TrayIconBuilder, TrayIconEvent, MouseButton};
-use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
 use crate::commands::theme;
+use crate::models::state::AppState;
+use crate::workflow::funasr_service::FunasrService;
+use tauri::Manager;
 
-/// 设置系统托盘
-pub fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+const TRAY_ID: &str = "main";
+const PROFILE_PREFIX: &str = "profile:";
+
+/// 创建托盘菜单
+fn build_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
     // 创建菜单项
     let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
     let settings_item = MenuItem::with_id(app, "settings", "设置", true, None::<&str>)?;
+    let meeting_item = MenuItem::with_id(app, "meeting", "开始/结束会议记录", true, None::<&str>)?;
+    let funasr_start_item = MenuItem::with_id(app, "funasr_start", "启动 FunASR 服务", true, None::<&str>)?;
+    let funasr_stop_item = MenuItem::with_id(app, "funasr_stop", "停止 FunASR 服务", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
 
+    // 配置方案子菜单（第一项为只使用全局配置）
+    let (profiles, active) = {
+        let config = app.state::<AppState>().config.lock().unwrap().clone();
+        (config.profiles, config.active_profile)
+    };
+    let profile_menu = Submenu::with_id(app, "profiles", "配置方案", true)?;
+    profile_menu.append(&CheckMenuItem::with_id(app, PROFILE_PREFIX, "全局配置", true, active.is_empty(), None::<&str>)?)?;
+    for profile in &profiles {
+        let id = format!("{}{}", PROFILE_PREFIX, profile.name);
+        profile_menu.append(&CheckMenuItem::with_id(app, id, &profile.name, true, profile.name == active, None::<&str>)?)?;
+    }
+
     // 创建菜单
-    let menu = Menu::with_items(
+    Menu::with_items(
         app,
         &[
             &show_item,
             &settings_item,
+            &meeting_item,
+            &profile_menu,
+            &PredefinedMenuItem::separator(app)?,
+            &funasr_start_item,
+            &funasr_stop_item,
             &PredefinedMenuItem::separator(app)?,
             &quit_item,
         ]
-    )?;
+    )
+}
+
+/// 配置方案变化后重建托盘菜单
+pub fn refresh_profiles(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else { return };
+    match build_menu(app) {
+        Ok(menu) => {
+            let _ = tray.set_menu(Some(menu));
+        }
+        Err(e) => log::error!("刷新托盘菜单失败: {}", e),
+    }
+}
+
+/// 设置系统托盘
+pub fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let menu = build_menu(app.handle())?;
 
     // 创建托盘图标
-    let tray = TrayIconBuilder::new()
+    let tray = TrayIconBuilder::with_id(TRAY_ID)
         .menu(&menu)
         .tooltip("语音助手")
         .icon(app.default_window_icon().unwrap().clone())
@@ -41,9 +83,33 @@ pub fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>
                     }
                 });
             }
+            "meeting" => {
+                let state = app.state::<AppState>();
+                if !crate::utils::recording_state::stop_meeting(&state, app) {
+                    if let Err(e) = crate::utils::recording_state::start_meeting(&state, app) {
+                        log::warn!("开始会议记录失败: {}", e);
+                    }
+                }
+            }
+            "funasr_start" => {
+                let service = app.state::<FunasrService>();
+                if let Err(e) = service.start() {
+                    log::error!("{}", e);
+                }
+            }
+            "funasr_stop" => {
+                app.state::<FunasrService>().stop();
+            }
             "quit" => {
                 app.exit(0);
             }
+            id if id.starts_with(PROFILE_PREFIX) => {
+                let name = id[PROFILE_PREFIX.len()..].to_string();
+                let result = crate::commands::settings::switch_profile(app.clone(), app.state::<AppState>(), name);
+                if let Err(e) = result {
+                    log::warn!("切换配置方案失败: {}", e);
+                }
+            }
             _ => {}
         }
     });
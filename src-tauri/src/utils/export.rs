@@ -0,0 +1,295 @@
+//! 转写导出
+//!
+//! 把一次会话的定稿句子导出为 SRT / WebVTT / 纯文本 / Markdown / JSON。
+//! JSON 为无损格式，同时用作会话存档，可再次导入后导出为其他格式
+
+use crate::asr::transcript::Segment;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 存档格式版本
+const ARCHIVE_VERSION: u32 = 1;
+/// 服务商未提供时间时按字数估算时长
+const MS_PER_CHAR: u64 = 250;
+const MIN_DURATION_MS: u64 = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Srt,
+    Vtt,
+    Txt,
+    Md,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_ascii_lowercase().as_str() {
+            "srt" => Ok(Self::Srt),
+            "vtt" | "webvtt" => Ok(Self::Vtt),
+            "txt" | "text" => Ok(Self::Txt),
+            "md" | "markdown" => Ok(Self::Md),
+            "json" => Ok(Self::Json),
+            _ => Err(format!("不支持的导出格式: {}", s)),
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Txt => "txt",
+            Self::Md => "md",
+            Self::Json => "json",
+        }
+    }
+
+    /// 按文件扩展名推断格式
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let ext = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+        Self::parse(ext)
+    }
+}
+
+/// 会话存档
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    /// 开始时间（Unix 秒）
+    pub started_at: u64,
+    pub provider: String,
+    pub segments: Vec<Segment>,
+}
+
+impl Session {
+    pub fn new(started_at: u64, provider: String, segments: Vec<Segment>) -> Self {
+        Self { version: ARCHIVE_VERSION, started_at, provider, segments }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("读取存档失败: {}", e))?;
+        serde_json::from_str(&content).map_err(|e| format!("存档格式错误: {}", e))
+    }
+}
+
+/// 按格式渲染会话
+pub fn render(session: &Session, format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Srt => render_cues(session, true),
+        ExportFormat::Vtt => render_cues(session, false),
+        ExportFormat::Txt => session.segments.iter().map(|s| format!("{}\n", line_text(s))).collect(),
+        ExportFormat::Md => render_markdown(session),
+        ExportFormat::Json => serde_json::to_string_pretty(session).unwrap_or_default(),
+    }
+}
+
+/// 渲染并写入文件
+pub fn export(session: &Session, format: ExportFormat, output: &Path) -> Result<(), String> {
+    std::fs::write(output, render(session, format)).map_err(|e| format!("写入 {} 失败: {}", output.display(), e))
+}
+
+/// 各句的起止时间，缺失时接在上一句之后按字数估算
+fn timeline(segments: &[Segment]) -> Vec<(u64, u64)> {
+    let mut last_end = 0;
+    segments
+        .iter()
+        .map(|s| {
+            let start = s.start_ms.unwrap_or(last_end);
+            let estimated = start + (s.text.chars().count() as u64 * MS_PER_CHAR).max(MIN_DURATION_MS);
+            let end = s.end_ms.filter(|&e| e > start).unwrap_or(estimated);
+            last_end = end;
+            (start, end)
+        })
+        .collect()
+}
+
+fn line_text(s: &Segment) -> String {
+    match &s.speaker {
+        Some(speaker) => format!("说话人{}: {}", speaker, s.text),
+        None => s.text.clone(),
+    }
+}
+
+/// SRT / WebVTT 字幕
+fn render_cues(session: &Session, srt: bool) -> String {
+    let mut out = if srt { String::new() } else { "WEBVTT\n\n".to_string() };
+    for (i, (s, (start, end))) in session.segments.iter().zip(timeline(&session.segments)).enumerate() {
+        if srt {
+            out.push_str(&format!("{}\n{} --> {}\n{}\n\n", i + 1, timestamp(start, ','), timestamp(end, ','), line_text(s)));
+        } else {
+            let text = match &s.speaker {
+                Some(speaker) => format!("<v 说话人{}>{}", speaker, s.text),
+                None => s.text.clone(),
+            };
+            out.push_str(&format!("{} --> {}\n{}\n\n", timestamp(start, '.'), timestamp(end, '.'), text));
+        }
+    }
+    out
+}
+
+fn render_markdown(session: &Session) -> String {
+    let mut out = format!("# 转写记录\n\n- 服务商: {}\n- 开始时间: {}\n\n", session.provider, httpdate::fmt_http_date(
+        std::time::UNIX_EPOCH + std::time::Duration::from_secs(session.started_at),
+    ));
+    for (s, (start, _)) in session.segments.iter().zip(timeline(&session.segments)) {
+        out.push_str(&format!("**[{}]** {}\n\n", clock(start), line_text(s)));
+    }
+    out
+}
+
+/// hh:mm:ss（超过 99 小时时小时位随之变宽）
+fn clock(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// hh:mm:ss + 分隔符 + 毫秒
+fn timestamp(ms: u64, sep: char) -> String {
+    format!("{}{}{:03}", clock(ms), sep, ms % 1000)
+}
+
+/// 命令行导出: --export <存档.json> [--format srt] [--output out.srt]
+///
+/// 未指定 --format 时按输出文件扩展名推断，未指定 --output 时输出到存档同目录
+pub fn run_cli(args: &[String]) -> Result<(), String> {
+    let value = |flag: &str| args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned();
+    let input = value("--export").ok_or("用法: --export <存档.json> [--format srt|vtt|txt|md|json] [--output 文件]")?;
+    let input = Path::new(&input);
+    let session = Session::load(input)?;
+
+    let (format, output) = match (value("--format"), value("--output")) {
+        (Some(f), Some(o)) => (ExportFormat::parse(&f)?, o.into()),
+        (None, Some(o)) => {
+            let o: std::path::PathBuf = o.into();
+            (ExportFormat::from_path(&o)?, o)
+        }
+        (Some(f), None) => {
+            let format = ExportFormat::parse(&f)?;
+            (format, input.with_extension(format.extension()))
+        }
+        (None, None) => (ExportFormat::Srt, input.with_extension("srt")),
+    };
+    if output == input {
+        return Err("输出文件不能覆盖存档".to_string());
+    }
+    export(&session, format, &output)?;
+    println!("已导出: {}", output.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(text: &str, start_ms: Option<u64>, end_ms: Option<u64>, speaker: Option<&str>) -> Segment {
+        Segment { text: text.to_string(), start_ms, end_ms, speaker: speaker.map(str::to_string) }
+    }
+
+    fn session() -> Session {
+        Session::new(
+            0,
+            "doubao".to_string(),
+            vec![
+                segment("大家好", Some(0), Some(1500), Some("1")),
+                segment("开始开会", Some(2000), Some(3250), None),
+            ],
+        )
+    }
+
+    #[test]
+    fn srt_output() {
+        let expected = "1\n00:00:00,000 --> 00:00:01,500\n说话人1: 大家好\n\n\
+                        2\n00:00:02,000 --> 00:00:03,250\n开始开会\n\n";
+        assert_eq!(render(&session(), ExportFormat::Srt), expected);
+    }
+
+    #[test]
+    fn vtt_output() {
+        let expected = "WEBVTT\n\n\
+                        00:00:00.000 --> 00:00:01.500\n<v 说话人1>大家好\n\n\
+                        00:00:02.000 --> 00:00:03.250\n开始开会\n\n";
+        assert_eq!(render(&session(), ExportFormat::Vtt), expected);
+    }
+
+    #[test]
+    fn txt_output() {
+        assert_eq!(render(&session(), ExportFormat::Txt), "说话人1: 大家好\n开始开会\n");
+    }
+
+    #[test]
+    fn markdown_output() {
+        let expected = "# 转写记录\n\n- 服务商: doubao\n- 开始时间: Thu, 01 Jan 1970 00:00:00 GMT\n\n\
+                        **[00:00:00]** 说话人1: 大家好\n\n\
+                        **[00:00:02]** 开始开会\n\n";
+        assert_eq!(render(&session(), ExportFormat::Md), expected);
+    }
+
+    #[test]
+    fn markdown_past_99_hours() {
+        let session = Session::new(0, "funasr".to_string(), vec![segment("晚安", Some(360_000_000), None, None)]);
+        assert!(render(&session, ExportFormat::Md).contains("**[100:00:00]** 晚安"));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let json = render(&session(), ExportFormat::Json);
+        let expected = r#"{
+  "version": 1,
+  "started_at": 0,
+  "provider": "doubao",
+  "segments": [
+    {
+      "text": "大家好",
+      "start_ms": 0,
+      "end_ms": 1500,
+      "speaker": "1"
+    },
+    {
+      "text": "开始开会",
+      "start_ms": 2000,
+      "end_ms": 3250,
+      "speaker": null
+    }
+  ]
+}"#;
+        assert_eq!(json, expected);
+        let parsed: Session = serde_json::from_str(&json).unwrap();
+        assert_eq!(render(&parsed, ExportFormat::Srt), render(&session(), ExportFormat::Srt));
+    }
+
+    #[test]
+    fn timestamp_table() {
+        let cases = [
+            (0, ',', "00:00:00,000"),
+            (999, ',', "00:00:00,999"),
+            (1_000, '.', "00:00:01.000"),
+            (59_999, ',', "00:00:59,999"),
+            (60_000, ',', "00:01:00,000"),
+            (3_599_999, '.', "00:59:59.999"),
+            (3_600_000, ',', "01:00:00,000"),
+            (359_999_999, ',', "99:59:59,999"),
+            (360_000_000, ',', "100:00:00,000"),
+        ];
+        for (ms, sep, expected) in cases {
+            assert_eq!(timestamp(ms, sep), expected, "ms: {}", ms);
+        }
+    }
+
+    #[test]
+    fn timeline_estimates_missing_times() {
+        let segments = [
+            // 缺少结束时间：按字数估算，不足 1 秒按 1 秒
+            segment("好", Some(500), None, None),
+            // 缺少开始时间：接在上一句之后
+            segment("一二三四五六", None, None, None),
+            // 结束时间不晚于开始时间时视为缺失
+            segment("再见", Some(10_000), Some(9_000), None),
+            segment("谢谢", Some(12_000), Some(12_800), None),
+        ];
+        assert_eq!(
+            timeline(&segments),
+            vec![(500, 1_500), (1_500, 3_000), (10_000, 11_000), (12_000, 12_800)]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! 单实例与命令行控制
+//!
+//! 再次启动程序时不会运行第二个实例，而是把命令行参数转发给正在运行的实例后退出
+//! （Unix 为运行时目录下的套接字文件，Windows 为命名管道，不依赖 D-Bus 等会话服务），
+//! 便于在全局快捷键不可靠的环境（如部分 Linux 窗口管理器）中用系统快捷键绑定
+//! `voicekit --toggle` 控制听写。
+//!
+//! 支持的参数:
+//! - `--toggle`         开始/停止听写（同快捷键）
+//! - `--start` / `--stop`
+//! - `--cancel`         取消听写，丢弃未输入的结果
+//! - `--profile <名称>` 切换配置方案（空字符串为全局配置）
+//!
+//! 会议转写进行中时忽略录音相关的操作（会议只能在会议界面结束）。
+
+use crate::models::state::AppState;
+use std::io::{BufRead, BufReader, Write};
+use std::sync::atomic::Ordering;
+use std::thread;
+use tauri::Manager;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliAction {
+    Toggle,
+    Start,
+    Stop,
+    Cancel,
+    Profile(String),
+}
+
+/// 解析命令行参数（第一个为程序路径，未知参数忽略）
+pub fn parse(args: &[String]) -> Vec<CliAction> {
+    let mut actions = Vec::new();
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--toggle" => actions.push(CliAction::Toggle),
+            "--start" => actions.push(CliAction::Start),
+            "--stop" => actions.push(CliAction::Stop),
+            "--cancel" => actions.push(CliAction::Cancel),
+            "--profile" => match iter.next() {
+                Some(name) => actions.push(CliAction::Profile(name.clone())),
+                None => log::warn!("--profile 缺少方案名称"),
+            },
+            other => log::debug!("忽略命令行参数: {}", other),
+        }
+    }
+    actions
+}
+
+/// 把参数转发给正在运行的实例（每次一行 JSON 数组），返回 false 表示没有正在运行的实例
+pub fn forward(args: &[String]) -> bool {
+    let Some(mut stream) = connect() else { return false };
+    let mut line = serde_json::to_string(args).unwrap_or_default();
+    line.push('\n');
+    match stream.write_all(line.as_bytes()).and_then(|_| stream.flush()) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("转发命令行参数失败: {}", e);
+            false
+        }
+    }
+}
+
+/// 监听其他实例转发的参数（在应用启动后调用）
+pub fn listen(app: tauri::AppHandle) {
+    thread::spawn(move || {
+        if let Err(e) = serve(&app) {
+            log::error!("命令行控制监听失败: {}", e);
+        }
+    });
+}
+
+/// 读取一次转发的参数并执行
+fn receive(app: &tauri::AppHandle, stream: impl std::io::Read) {
+    let mut line = String::new();
+    if let Err(e) = BufReader::new(stream).read_line(&mut line) {
+        log::warn!("读取转发的参数失败: {}", e);
+        return;
+    }
+    match serde_json::from_str::<Vec<String>>(&line) {
+        Ok(args) => handle(app, &args),
+        Err(e) => log::warn!("转发的参数格式错误: {}", e),
+    }
+}
+
+/// 套接字文件（按用户区分，优先放在 XDG_RUNTIME_DIR 中）
+#[cfg(unix)]
+fn socket_path() -> std::path::PathBuf {
+    let dir = std::env::var_os("XDG_RUNTIME_DIR").map(std::path::PathBuf::from).unwrap_or_else(std::env::temp_dir);
+    let user = std::env::var("USER").unwrap_or_default();
+    dir.join(format!("voicekit-{}.sock", user))
+}
+
+#[cfg(unix)]
+fn connect() -> Option<std::os::unix::net::UnixStream> {
+    std::os::unix::net::UnixStream::connect(socket_path()).ok()
+}
+
+#[cfg(unix)]
+fn serve(app: &tauri::AppHandle) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixListener;
+
+    let path = socket_path();
+    // 上次异常退出留下的套接字文件无人监听，删除后重新创建
+    if path.exists() && connect().is_none() {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    log::info!("命令行控制已监听: {}", path.display());
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                // 连接后不发送数据的客户端不应阻塞后续转发
+                let _ = stream.set_read_timeout(Some(std::time::Duration::from_secs(1)));
+                receive(app, stream);
+            }
+            Err(e) => log::warn!("接受命令行控制连接失败: {}", e),
+        }
+    }
+    Ok(())
+}
+
+/// 命名管道（按用户区分）
+#[cfg(windows)]
+fn pipe_name() -> String {
+    format!(r"\\.\pipe\voicekit-{}", std::env::var("USERNAME").unwrap_or_default())
+}
+
+#[cfg(windows)]
+fn connect() -> Option<std::fs::File> {
+    // 管道实例正忙（ERROR_PIPE_BUSY）时稍后重试
+    const ERROR_PIPE_BUSY: i32 = 231;
+    for _ in 0..20 {
+        match std::fs::OpenOptions::new().write(true).open(pipe_name()) {
+            Ok(file) => return Some(file),
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY) => thread::sleep(std::time::Duration::from_millis(50)),
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
+#[cfg(windows)]
+fn serve(app: &tauri::AppHandle) -> std::io::Result<()> {
+    use tokio::io::AsyncReadExt;
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let rt = tokio::runtime::Runtime::new()?;
+    rt.block_on(async {
+        let name = pipe_name();
+        // 只允许创建第一个实例，已有程序在监听时失败
+        let mut server = ServerOptions::new().first_pipe_instance(true).create(&name)?;
+        log::info!("命令行控制已监听: {}", name);
+        loop {
+            server.connect().await?;
+            let mut connected = std::mem::replace(&mut server, ServerOptions::new().create(&name)?);
+            let mut data = Vec::new();
+            let read = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+                let mut buf = [0u8; 1024];
+                // 读到换行为止（客户端写完一行即关闭）
+                while !data.contains(&b'\n') {
+                    match connected.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => data.extend_from_slice(&buf[..n]),
+                    }
+                }
+            });
+            if read.await.is_err() {
+                log::warn!("读取转发的参数超时");
+            }
+            receive(app, data.as_slice());
+        }
+    })
+}
+
+/// 执行命令行参数中的操作（启动时和收到其他实例转发的参数时调用）
+pub fn handle(app: &tauri::AppHandle, args: &[String]) {
+    let state = app.state::<AppState>();
+    for action in parse(args) {
+        log::info!("命令行操作: {:?}", action);
+        let recording_action = !matches!(action, CliAction::Profile(_));
+        if recording_action && state.is_meeting.load(Ordering::SeqCst) {
+            log::warn!("正在会议转写，忽略命令行操作: {:?}", action);
+            continue;
+        }
+        match action {
+            CliAction::Toggle => crate::commands::theme::toggle_recording(app),
+            CliAction::Start | CliAction::Stop => {
+                let recording = action == CliAction::Start;
+                if *state.is_recording.lock().unwrap() != recording {
+                    crate::commands::theme::toggle_recording(app);
+                }
+            }
+            CliAction::Cancel => {
+                super::recording_state::cancel(&state, app);
+            }
+            CliAction::Profile(name) => {
+                if let Err(e) = crate::commands::settings::switch_profile(app.clone(), app.state::<AppState>(), name) {
+                    log::warn!("切换配置方案失败: {}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        std::iter::once("voicekit").chain(list.iter().copied()).map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_keeps_action_order() {
+        assert_eq!(
+            parse(&args(&["--profile", "coding", "--start", "--stop", "--toggle", "--cancel"])),
+            vec![
+                CliAction::Profile("coding".to_string()),
+                CliAction::Start,
+                CliAction::Stop,
+                CliAction::Toggle,
+                CliAction::Cancel,
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_ignores_unknown_flags() {
+        assert_eq!(parse(&args(&["--verbose", "--toggle", "extra"])), vec![CliAction::Toggle]);
+        // 程序路径本身不是参数
+        assert!(parse(&["--toggle".to_string()]).is_empty());
+    }
+
+    #[test]
+    fn parse_profile_requires_name() {
+        assert_eq!(parse(&args(&["--toggle", "--profile"])), vec![CliAction::Toggle]);
+        assert_eq!(parse(&args(&["--profile", ""])), vec![CliAction::Profile(String::new())]);
+    }
+}
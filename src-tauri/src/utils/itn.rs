@@ -0,0 +1,610 @@
+//! 文本规整（逆文本正则化）
+//!
+//! 把口语形式的中英文数字、日期、时间、百分比、货币、单位转换为书面形式，
+//! 例如 "二零二六年十月十七号" → "2026年10月17日"，"百分之五十" → "50%"，
+//! "twenty five percent" → "25%"。各类规则可单独开关。
+
+use crate::models::config::ItnConfig;
+use regex::{Captures, Regex};
+
+// ============== 中文 ==============
+
+const CN_NUM: &str = "[零〇一二两三四五六七八九十百千万亿]";
+const CN_DIGIT: &str = "[零〇一二两三四五六七八九]";
+
+lazy_static::lazy_static! {
+    static ref CN_PERCENT: Regex = Regex::new(&format!("百分之({CN_NUM}+(?:点{CN_DIGIT}+)?)")).unwrap();
+    // 年份开头（可带月、日）或月份开头（可带日），至少包含一个 年/月 锚点
+    static ref CN_DATE: Regex = Regex::new(&format!(
+        "({CN_DIGIT}{{4}}|{CN_DIGIT}{{2}})年(?:({CN_NUM}{{1,3}})月(?:({CN_NUM}{{1,3}})[日号])?)?|({CN_NUM}{{1,3}})月(?:({CN_NUM}{{1,3}})[日号])?"
+    )).unwrap();
+    static ref CN_TIME: Regex = Regex::new(&format!("({CN_NUM}{{1,3}})点(?:({CN_NUM}{{1,3}})分|(半)|(整))")).unwrap();
+    static ref CN_CURRENCY: Regex = Regex::new(&format!("({CN_NUM}+(?:点{CN_DIGIT}+)?)(美元|欧元|英镑|块钱|元)")).unwrap();
+    static ref CN_UNIT: Regex = Regex::new(&format!(
+        "({CN_NUM}+(?:点{CN_DIGIT}+)?)(公里|千米|厘米|毫米|米|公斤|千克|克|毫升|升|摄氏度|小时|分钟|秒钟|岁)"
+    )).unwrap();
+    static ref CN_NUMBER: Regex = Regex::new(&format!("{CN_NUM}{{2,}}(?:点{CN_DIGIT}+)?")).unwrap();
+}
+
+/// 含数字的固定说法，整体保持原样（"三十而立" 不是 "30而立"，"五月天" 是乐队名）
+const FIXED_PHRASES: [&str; 9] = [
+    "三十而立", "四十不惑", "五十知天命", "六十耳顺", "七十古来稀",
+    "三十六计", "十八般武艺", "十万火急", "五月天",
+];
+
+/// 匹配范围是否与文本中的固定说法重叠
+fn in_fixed_phrase(text: &str, m: regex::Match) -> bool {
+    FIXED_PHRASES.iter().any(|p| {
+        text.match_indices(p).any(|(i, _)| i < m.end() && m.start() < i + p.len())
+    })
+}
+
+fn cn_digit(c: char) -> Option<u64> {
+    "零一二三四五六七八九".chars().position(|d| d == c).map(|v| v as u64).or(match c {
+        '〇' => Some(0),
+        '两' => Some(2),
+        _ => None,
+    })
+}
+
+/// 逐位读法: "二零二六" → "2026"
+fn cn_digits(s: &str) -> Option<String> {
+    s.chars().map(|c| cn_digit(c).and_then(|d| char::from_digit(d as u32, 10))).collect()
+}
+
+/// 中文数值: "三百二十" → 320，"十二" → 12，"一万零五" → 10005（结构不合法时返回 None）
+fn cn_value(s: &str) -> Option<u64> {
+    let (mut total, mut section, mut digit) = (0u64, 0u64, None::<u64>);
+    let mut last_unit = u64::MAX;
+    for c in s.chars() {
+        if let Some(d) = cn_digit(c) {
+            // 连续两个数字（如 "三四十"）不是合法数值
+            if digit.is_some_and(|v| v != 0) && d != 0 {
+                return None;
+            }
+            digit = Some(d);
+            continue;
+        }
+        let unit = match c {
+            '十' => 10,
+            '百' => 100,
+            '千' => 1000,
+            '万' => 10_000,
+            '亿' => 100_000_000,
+            _ => return None,
+        };
+        if unit >= 10_000 {
+            section = (section + digit.unwrap_or(0)) * unit;
+            if section == 0 {
+                return None;
+            }
+            total += section;
+            section = 0;
+        } else {
+            if unit >= last_unit {
+                return None;
+            }
+            // "十二" 省略了 "一"
+            let d = match digit {
+                Some(d) => d.max(1),
+                None if unit == 10 => 1,
+                None => return None,
+            };
+            section += d * unit;
+            last_unit = unit;
+        }
+        digit = None;
+        if unit >= 10_000 {
+            last_unit = u64::MAX;
+        }
+    }
+    Some(total + section + digit.unwrap_or(0))
+}
+
+/// 带小数的中文数值: "三点五" → "3.5"
+fn cn_decimal(s: &str) -> Option<String> {
+    match s.split_once('点') {
+        Some((int, frac)) => Some(format!("{}.{}", cn_value(int)?, cn_digits(frac)?)),
+        None => cn_value(s).map(|v| v.to_string()),
+    }
+}
+
+fn cn_percent(text: &str) -> String {
+    CN_PERCENT
+        .replace_all(text, |c: &Captures| match cn_decimal(&c[1]) {
+            Some(v) => format!("{}%", v),
+            None => c[0].to_string(),
+        })
+        .into_owned()
+}
+
+fn cn_date(text: &str) -> String {
+    CN_DATE
+        .replace_all(text, |c: &Captures| {
+            if in_fixed_phrase(text, c.get(0).unwrap()) {
+                return c[0].to_string();
+            }
+            let year = c.get(1).map(|m| cn_digits(m.as_str()));
+            let month = c.get(2).or(c.get(4)).map(|m| cn_value(m.as_str()).filter(|v| (1..=12).contains(v)));
+            let day = c.get(3).or(c.get(5)).map(|m| cn_value(m.as_str()).filter(|v| (1..=31).contains(v)));
+            // 任一部分无法解析，或只有孤立的日期片段不足以判断时保持原样
+            if matches!(year, Some(None)) || matches!(month, Some(None)) || matches!(day, Some(None)) {
+                return c[0].to_string();
+            }
+            let mut out = String::new();
+            if let Some(Some(y)) = year {
+                out.push_str(&format!("{}年", y));
+            }
+            if let Some(Some(m)) = month {
+                out.push_str(&format!("{}月", m));
+            }
+            if let Some(Some(d)) = day {
+                out.push_str(&format!("{}日", d));
+            }
+            out
+        })
+        .into_owned()
+}
+
+fn cn_time(text: &str) -> String {
+    CN_TIME
+        .replace_all(text, |c: &Captures| {
+            let hour = cn_value(&c[1]).filter(|h| *h <= 24);
+            let minute = match (c.get(2), c.get(3)) {
+                (Some(m), _) => cn_value(m.as_str()).filter(|m| *m < 60),
+                (None, Some(_)) => Some(30),
+                _ => Some(0),
+            };
+            match (hour, minute) {
+                (Some(h), Some(m)) => format!("{}:{:02}", h, m),
+                _ => c[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+fn cn_currency(text: &str) -> String {
+    CN_CURRENCY
+        .replace_all(text, |c: &Captures| {
+            let Some(v) = cn_decimal(&c[1]) else { return c[0].to_string() };
+            match &c[2] {
+                "美元" => format!("${}", v),
+                "欧元" => format!("€{}", v),
+                "英镑" => format!("£{}", v),
+                _ => format!("{}元", v),
+            }
+        })
+        .into_owned()
+}
+
+fn cn_unit(text: &str) -> String {
+    CN_UNIT
+        .replace_all(text, |c: &Captures| {
+            let Some(v) = cn_decimal(&c[1]) else { return c[0].to_string() };
+            let unit = match &c[2] {
+                "公里" | "千米" => "km",
+                "厘米" => "cm",
+                "毫米" => "mm",
+                "公斤" | "千克" => "kg",
+                "毫升" => "ml",
+                "摄氏度" => "℃",
+                other => other,
+            };
+            format!("{}{}", v, unit)
+        })
+        .into_owned()
+}
+
+/// 一般数字：至少两个字，含单位的按数值读（"三百二十" → "320"），
+/// 不含单位且至少三位的按逐位读（"一三八" → "138"），其余（如 "一两"）保持原样。
+/// 逐位读法不用 "两"，也不会是 AABB 式的叠字（"三三两两"、"七七八八" 多为成语），
+/// 固定说法（见 [`FIXED_PHRASES`]）保持原样
+fn cn_number(text: &str) -> String {
+    CN_NUMBER
+        .replace_all(text, |c: &Captures| {
+            let s = &c[0];
+            if in_fixed_phrase(text, c.get(0).unwrap()) {
+                return s.to_string();
+            }
+            let has_unit = s.chars().any(|ch| "十百千万亿".contains(ch));
+            let converted = if s.starts_with(['百', '千', '万', '亿']) {
+                None
+            } else if has_unit {
+                cn_decimal(s)
+            } else if s.chars().count() >= 3 && !s.contains(['点', '两']) && !is_reduplicated(s) {
+                cn_digits(s)
+            } else {
+                None
+            };
+            converted.unwrap_or_else(|| s.to_string())
+        })
+        .into_owned()
+}
+
+/// AABB 式叠字: "七七八八"
+fn is_reduplicated(s: &str) -> bool {
+    let chars: Vec<char> = s.chars().collect();
+    chars.len() == 4 && chars[0] == chars[1] && chars[2] == chars[3] && chars[0] != chars[2]
+}
+
+// ============== 英文 ==============
+
+const EN_NUM: &str = "(?:zero|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve|thirteen|fourteen|fifteen|sixteen|seventeen|eighteen|nineteen|twenty|thirty|forty|fifty|sixty|seventy|eighty|ninety|hundred|thousand|million|billion)";
+const EN_MONTHS: [&str; 12] = [
+    "january", "february", "march", "april", "may", "june",
+    "july", "august", "september", "october", "november", "december",
+];
+const EN_ORDINALS: [&str; 19] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+    "eleventh", "twelfth", "thirteenth", "fourteenth", "fifteenth", "sixteenth", "seventeenth",
+    "eighteenth", "nineteenth",
+];
+
+fn en_phrase() -> String {
+    format!(r"{EN_NUM}(?:(?:\s+|-)(?:and\s+)?{EN_NUM})*(?:\s+point(?:\s+{EN_NUM})+)?")
+}
+
+lazy_static::lazy_static! {
+    static ref EN_DATE: Regex = {
+        let ordinal = format!(
+            r"(?:(?:twenty|thirty)[\s-])?(?:{}|twentieth|thirtieth)",
+            EN_ORDINALS.join("|")
+        );
+        Regex::new(&format!(
+            r"(?i)\b({})\s+({ordinal}|{phrase})(?:,?\s+({phrase}))?\b",
+            EN_MONTHS.join("|"),
+            phrase = en_phrase()
+        )).unwrap()
+    };
+    static ref EN_NUMBER: Regex = Regex::new(&format!(
+        r"(?i)\b({})\b(?:\s+(percent|per cent|dollars?|bucks|euros?|yuan|o'clock|[ap]\.?m\b\.?|kilometers?|kilometres?|centimeters?|centimetres?|millimeters?|millimetres?|meters?|metres?|kilograms?|grams?|miles?|liters?|litres?|degrees? celsius))?",
+        en_phrase()
+    )).unwrap();
+}
+
+fn en_word(w: &str) -> Option<u64> {
+    const SMALL: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+        "eleven", "twelve", "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+    ];
+    const TENS: [&str; 8] = ["twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+    let w = w.to_lowercase();
+    SMALL.iter().position(|s| *s == w).map(|v| v as u64)
+        .or_else(|| TENS.iter().position(|s| *s == w).map(|v| (v as u64 + 2) * 10))
+        .or(match w.as_str() {
+            "hundred" => Some(100),
+            "thousand" => Some(1_000),
+            "million" => Some(1_000_000),
+            "billion" => Some(1_000_000_000),
+            _ => None,
+        })
+}
+
+fn en_words(s: &str) -> Vec<&str> {
+    s.split(|c: char| c.is_whitespace() || c == '-')
+        .filter(|w| !w.is_empty() && !w.eq_ignore_ascii_case("and"))
+        .collect()
+}
+
+/// 英文数值: "three hundred and five" → 305（"one two" 等不合法组合返回 None）
+fn en_value(words: &[&str]) -> Option<u64> {
+    let (mut total, mut current) = (0u64, 0u64);
+    let mut prev: Option<u64> = None;
+    for w in words {
+        let v = en_word(w)?;
+        match v {
+            100 => {
+                if prev.is_some_and(|p| p >= 100) {
+                    return None;
+                }
+                current = current.max(1) * 100;
+            }
+            1_000.. => {
+                total += current.max(1) * v;
+                current = 0;
+            }
+            _ => {
+                // 个位/十几之后只能接量级词，整十之后只能接个位
+                match prev {
+                    Some(p) if p < 20 => return None,
+                    Some(p) if p < 100 && (v >= 10 || p % 10 != 0) => return None,
+                    _ => {}
+                }
+                current += v;
+            }
+        }
+        prev = Some(v);
+    }
+    prev.map(|_| total + current)
+}
+
+/// 带小数的英文数值: "three point five" → "3.5"
+fn en_decimal(s: &str) -> Option<String> {
+    let lower = s.to_lowercase();
+    match lower.split_once(" point ") {
+        Some((int, frac)) => {
+            let frac: Option<String> = en_words(frac)
+                .iter()
+                .map(|w| en_word(w).filter(|d| *d < 10).map(|d| d.to_string()))
+                .collect();
+            Some(format!("{}.{}", en_value(&en_words(int))?, frac?))
+        }
+        None => en_value(&en_words(&lower)).map(|v| v.to_string()),
+    }
+}
+
+/// 年份: "twenty twenty six" → 2026，"two thousand twenty six" → 2026
+fn en_year(s: &str) -> Option<u64> {
+    let words = en_words(s);
+    if let Some(v) = en_value(&words).filter(|v| *v >= 1000) {
+        return Some(v);
+    }
+    (1..words.len()).find_map(|i| {
+        let hi = en_value(&words[..i]).filter(|v| (10..100).contains(v))?;
+        let lo = en_value(&words[i..]).filter(|v| *v < 100)?;
+        Some(hi * 100 + lo)
+    })
+}
+
+fn en_ordinal(s: &str) -> Option<u64> {
+    let words = en_words(s);
+    let (tens, last) = match words.as_slice() {
+        [last] => (0, last.to_lowercase()),
+        [tens, last] => (en_value(&[tens]).filter(|t| *t == 20 || *t == 30)?, last.to_lowercase()),
+        _ => return None,
+    };
+    let v = match last.as_str() {
+        "twentieth" => 20,
+        "thirtieth" => 30,
+        other => EN_ORDINALS.iter().position(|o| *o == other)? as u64 + 1,
+    };
+    Some(tens + v)
+}
+
+fn en_date(text: &str) -> String {
+    EN_DATE
+        .replace_all(text, |c: &Captures| {
+            // 小写的 "may" 多为情态动词
+            if &c[1] == "may" {
+                return c[0].to_string();
+            }
+            let day = en_ordinal(&c[2]).or_else(|| en_value(&en_words(&c[2]))).filter(|d| (1..=31).contains(d));
+            let year = c.get(3).map(|m| en_year(m.as_str()));
+            let (Some(day), false) = (day, matches!(year, Some(None))) else {
+                return c[0].to_string();
+            };
+            let mut month = c[1].to_lowercase();
+            month[..1].make_ascii_uppercase();
+            match year {
+                Some(Some(y)) => format!("{} {}, {}", month, day, y),
+                _ => format!("{} {}", month, day),
+            }
+        })
+        .into_owned()
+}
+
+fn en_time(words: &[&str], suffix: &str) -> Option<String> {
+    let hour = en_value(&words[..1]).filter(|h| (1..=12).contains(h))?;
+    let minute = if words.len() > 1 { en_value(&words[1..]).filter(|m| *m < 60)? } else { 0 };
+    let period = suffix.replace('.', "").to_uppercase();
+    Some(match period.as_str() {
+        "O'CLOCK" => format!("{}:{:02}", hour, minute),
+        _ => format!("{}:{:02} {}", hour, minute, period),
+    })
+}
+
+fn en_number(text: &str, config: &ItnConfig) -> String {
+    EN_NUMBER
+        .replace_all(text, |c: &Captures| {
+            let phrase = &c[1];
+            let suffix = c.get(2).map(|m| m.as_str().to_lowercase());
+            let converted = match suffix.as_deref() {
+                Some(s) if config.times && is_time_suffix(s) => en_time(&en_words(phrase), s),
+                Some("percent" | "per cent") if config.percentages => en_decimal(phrase).map(|v| format!("{}%", v)),
+                Some(s) if config.currencies && (s.starts_with("dollar") || s == "bucks") => {
+                    en_decimal(phrase).map(|v| format!("${}", v))
+                }
+                Some(s) if config.currencies && s.starts_with("euro") => en_decimal(phrase).map(|v| format!("€{}", v)),
+                Some("yuan") if config.currencies => en_decimal(phrase).map(|v| format!("¥{}", v)),
+                Some(s) if config.units && en_unit(s).is_some() => {
+                    en_decimal(phrase).zip(en_unit(s)).map(|(v, u)| format!("{} {}", v, u))
+                }
+                _ if config.numbers => {
+                    // 单个小于 10 的数字保持英文写法（"one of them"）
+                    let words = en_words(phrase);
+                    en_decimal(phrase)
+                        .filter(|_| words.len() > 1 || en_value(&words).is_some_and(|v| v >= 10))
+                        .map(|v| format!("{}{}", v, &c[0][phrase.len()..]))
+                }
+                _ => None,
+            };
+            converted.unwrap_or_else(|| c[0].to_string())
+        })
+        .into_owned()
+}
+
+fn is_time_suffix(s: &str) -> bool {
+    s == "o'clock" || matches!(s.replace('.', "").as_str(), "am" | "pm")
+}
+
+fn en_unit(unit: &str) -> Option<&'static str> {
+    Some(match unit.trim_end_matches('s') {
+        "kilometer" | "kilometre" => "km",
+        "centimeter" | "centimetre" => "cm",
+        "millimeter" | "millimetre" => "mm",
+        "meter" | "metre" => "m",
+        "kilogram" => "kg",
+        "gram" => "g",
+        "mile" => "mi",
+        "liter" | "litre" => "L",
+        _ if unit.starts_with("degree") => "℃",
+        _ => return None,
+    })
+}
+
+// ============== 入口 ==============
+
+/// 按配置对整句文本做规整（先处理带上下文的规则，最后处理一般数字）
+pub fn normalize(text: &str, config: &ItnConfig) -> String {
+    let mut text = text.to_string();
+    if config.percentages {
+        text = cn_percent(&text);
+    }
+    if config.dates {
+        text = cn_date(&text);
+        text = en_date(&text);
+    }
+    if config.times {
+        text = cn_time(&text);
+    }
+    if config.currencies {
+        text = cn_currency(&text);
+    }
+    if config.units {
+        text = cn_unit(&text);
+    }
+    if config.numbers {
+        text = cn_number(&text);
+    }
+    en_number(&text, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all() -> ItnConfig {
+        ItnConfig { enabled: true, ..Default::default() }
+    }
+
+    #[test]
+    fn normalize_table() {
+        let cases = [
+            // 数字
+            ("一百二十三", "123"),
+            ("一三八", "138"),
+            ("一千零一", "1001"),
+            ("两千", "2000"),
+            ("一万零五", "10005"),
+            ("一九九八年", "1998年"),
+            ("九八年", "98年"),
+            ("nine hundred ninety nine", "999"),
+            ("three hundred and five", "305"),
+            ("twenty one", "21"),
+            ("twelve", "12"),
+            ("one million", "1000000"),
+            // 日期
+            ("二零二六年十月十七号", "2026年10月17日"),
+            ("十月一号", "10月1日"),
+            ("十二月二十五日", "12月25日"),
+            ("八月十五号", "8月15日"),
+            ("三月", "3月"),
+            ("去年三月", "去年3月"),
+            ("october seventeenth twenty twenty six", "October 17, 2026"),
+            ("march third", "March 3"),
+            ("december twenty fifth", "December 25"),
+            ("july fourth twenty twenty", "July 4, 2020"),
+            ("january first two thousand twenty six", "January 1, 2026"),
+            ("May fifth", "May 5"),
+            // 时间
+            ("三点半", "3:30"),
+            ("下午两点十五分", "下午2:15"),
+            ("十点整", "10:00"),
+            ("十二点零五分", "12:05"),
+            ("八点四十五分", "8:45"),
+            ("three o'clock", "3:00"),
+            ("twelve o'clock", "12:00"),
+            ("seven thirty pm", "7:30 PM"),
+            ("eleven a.m.", "11:00 AM"),
+            // 百分比
+            ("百分之五十", "50%"),
+            ("百分之三点五", "3.5%"),
+            ("百分之一百", "100%"),
+            ("百分之零点五", "0.5%"),
+            ("twenty five percent", "25%"),
+            ("ten percent", "10%"),
+            ("fifty per cent", "50%"),
+            // 货币
+            ("三百二十块钱", "320元"),
+            ("一千元", "1000元"),
+            ("五十美元", "$50"),
+            ("五欧元", "€5"),
+            ("十英镑", "£10"),
+            ("twenty dollars", "$20"),
+            ("one hundred dollars", "$100"),
+            ("five bucks", "$5"),
+            ("ten euros", "€10"),
+            ("fifty yuan", "¥50"),
+            // 单位
+            ("三点五公里", "3.5km"),
+            ("五点五公斤", "5.5kg"),
+            ("十厘米", "10cm"),
+            ("五毫升", "5ml"),
+            ("三十五摄氏度", "35℃"),
+            ("二十分钟", "20分钟"),
+            ("十岁", "10岁"),
+            ("three point five kilometers", "3.5 km"),
+            ("two meters", "2 m"),
+            ("ten miles", "10 mi"),
+            ("five kilograms", "5 kg"),
+            ("one hundred grams", "100 g"),
+            ("three liters", "3 L"),
+            ("twenty degrees celsius", "20 ℃"),
+            // 不转换
+            ("三三两两", "三三两两"),
+            ("七七八八", "七七八八"),
+            ("一心一意", "一心一意"),
+            ("一五一十", "一五一十"),
+            ("乱七八糟", "乱七八糟"),
+            ("万一", "万一"),
+            ("三十而立", "三十而立"),
+            ("他四十不惑", "他四十不惑"),
+            ("十万火急", "十万火急"),
+            ("五月天", "五月天"),
+            ("五月天的演唱会", "五月天的演唱会"),
+            ("十二三岁", "十二三岁"),
+            ("五六个", "五六个"),
+            ("两个小时", "两个小时"),
+            ("一点", "一点"),
+            ("二十五点", "25点"),
+            ("one of them", "one of them"),
+            ("one two", "one two"),
+            ("may I help you", "may I help you"),
+            ("March 3rd", "March 3rd"),
+            ("thirteen o'clock", "thirteen o'clock"),
+            ("今天天气不错", "今天天气不错"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(normalize(input, &all()), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn disabled_rules_are_skipped() {
+        let cases = [
+            (ItnConfig { dates: false, ..all() }, "十月一号", "十月一号"),
+            (ItnConfig { dates: false, ..all() }, "march third", "march third"),
+            (ItnConfig { numbers: false, ..all() }, "一百二十三", "一百二十三"),
+            (ItnConfig { numbers: false, ..all() }, "twenty one", "twenty one"),
+            (ItnConfig { times: false, ..all() }, "三点半", "三点半"),
+            (ItnConfig { times: false, ..all() }, "seven thirty pm", "seven thirty pm"),
+            (ItnConfig { percentages: false, ..all() }, "百分之五", "百分之五"),
+            (ItnConfig { percentages: false, ..all() }, "five percent", "five percent"),
+            (ItnConfig { currencies: false, ..all() }, "五美元", "五美元"),
+            (ItnConfig { currencies: false, ..all() }, "twenty dollars", "20 dollars"),
+            (ItnConfig { units: false, ..all() }, "三点五公里", "三点五公里"),
+            (ItnConfig { units: false, ..all() }, "five kilograms", "five kilograms"),
+            (ItnConfig { units: false, ..all() }, "twenty kilometers", "20 kilometers"),
+        ];
+        for (config, input, expected) in cases {
+            assert_eq!(normalize(input, &config), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn date_requires_anchor() {
+        assert!(CN_DATE.find("").is_none());
+        assert!(CN_DATE.find("今天").is_none());
+        assert_eq!(CN_DATE.find("去年三月").map(|m| m.as_str()), Some("三月"));
+    }
+}
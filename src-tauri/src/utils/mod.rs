@@ -2,3 +2,14 @@ pub mod shortcut;
 pub mod window_ext;
 pub mod recording_state;
 pub mod text_diff;
+pub mod vocabulary;
+pub mod text_rules;
+pub mod spoken_command;
+pub mod post_edit;
+pub mod itn;
+pub mod punctuation;
+pub mod translate;
+pub mod export;
+pub mod secrets;
+pub mod instance;
+pub mod output;
@@ -0,0 +1,163 @@
+//! 识别结果输出方式
+//!
+//! 默认模拟键盘输入到当前焦点（实时输出，识别过程中逐字修正）；其余方式只接收定稿的句子，
+//! 不需要焦点在输入框中，便于把听写结果交给笔记工具或脚本:
+//! - clipboard: 复制到剪贴板（本次听写的全部文本）
+//! - file: 追加写入文件，每句一行
+//! - stdout: 输出到标准输出，每句一行（从终端启动时可用管道交给其他命令）
+//! - http: POST {"text": ...} 到指定地址
+
+use crate::models::buffer::TextBuffer;
+use crate::models::config::{OutputConfig, OutputMode};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+lazy_static::lazy_static! {
+    // 剪贴板对象须在进程内一直存在，否则部分平台（X11）上复制的内容会随之丢失
+    static ref CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+}
+
+/// 输出方式
+pub trait OutputSink: Send + Sync {
+    /// 是否实时输出：是则识别过程中通过 revise 逐字修正，否则只通过 commit 接收定稿的句子
+    fn is_live(&self) -> bool {
+        false
+    }
+
+    /// 删除末尾 backspace 个字符后追加 text（可含控制字符，仅实时输出方式调用）
+    fn revise(&self, _backspace: usize, _text: &str) {}
+
+    /// 输出定稿的一句（仅非实时输出方式调用）
+    fn commit(&self, _text: &str) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// 按配置创建输出方式，keystroke 写入 text_buffer 由输入模拟器输入
+pub fn build(config: &OutputConfig, text_buffer: Arc<TextBuffer>) -> Result<Arc<dyn OutputSink>, String> {
+    Ok(match config.mode {
+        OutputMode::Keystroke => Arc::new(BufferSink::new(text_buffer)),
+        OutputMode::Clipboard => Arc::new(ClipboardSink::default()),
+        OutputMode::File => Arc::new(FileSink { path: config.file_path.trim().into() }),
+        OutputMode::Stdout => Arc::new(StdoutSink),
+        OutputMode::Http => Arc::new(HttpSink::new(config)?),
+    })
+}
+
+/// 实时写入文本缓冲区：keystroke 模式的缓冲区由输入模拟器逐字输入，会议模式的缓冲区不输入
+pub struct BufferSink {
+    text_buffer: Arc<TextBuffer>,
+}
+
+impl BufferSink {
+    pub fn new(text_buffer: Arc<TextBuffer>) -> Self {
+        Self { text_buffer }
+    }
+}
+
+impl OutputSink for BufferSink {
+    fn is_live(&self) -> bool {
+        true
+    }
+
+    fn revise(&self, backspace: usize, text: &str) {
+        // 先发送退格键，再发送新增文本（每个字符都是独立的队列元素）
+        self.text_buffer.push_backspaces(backspace);
+        if !text.is_empty() {
+            self.text_buffer.push_text(text);
+        }
+    }
+}
+
+/// 复制到剪贴板，内容为本次听写已定稿的全部文本
+#[derive(Default)]
+pub struct ClipboardSink {
+    text: Mutex<String>,
+}
+
+impl OutputSink for ClipboardSink {
+    fn commit(&self, text: &str) -> Result<(), String> {
+        let mut all = self.text.lock().unwrap();
+        all.push_str(text);
+        let mut clipboard = CLIPBOARD.lock().unwrap();
+        if clipboard.is_none() {
+            *clipboard = Some(arboard::Clipboard::new().map_err(|e| format!("打开剪贴板失败: {}", e))?);
+        }
+        clipboard
+            .as_mut()
+            .unwrap()
+            .set_text(all.clone())
+            .map_err(|e| format!("写入剪贴板失败: {}", e))
+    }
+}
+
+/// 追加写入文件
+pub struct FileSink {
+    path: std::path::PathBuf,
+}
+
+impl OutputSink for FileSink {
+    fn commit(&self, text: &str) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut f| writeln!(f, "{}", text))
+            .map_err(|e| format!("写入 {} 失败: {}", self.path.display(), e))
+    }
+}
+
+/// 输出到标准输出，每句写完即刷新（管道下标准输出为块缓冲，不刷新会积压到退出时）
+pub struct StdoutSink;
+
+impl OutputSink for StdoutSink {
+    fn commit(&self, text: &str) -> Result<(), String> {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", text)
+            .and_then(|_| stdout.flush())
+            .map_err(|e| format!("写入标准输出失败: {}", e))
+    }
+}
+
+/// POST 到本地 HTTP 地址（按顺序发送，失败只记录日志）
+pub struct HttpSink {
+    queue: mpsc::UnboundedSender<String>,
+}
+
+impl HttpSink {
+    fn new(config: &OutputConfig) -> Result<Self, String> {
+        let runtime = tokio::runtime::Handle::try_current().map_err(|e| format!("HTTP 输出需要异步运行时: {}", e))?;
+        let endpoint = config.endpoint.trim().to_string();
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_millis(config.timeout_ms))
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        // 会话结束后发送端随 Provider 释放，队列发送完毕后任务退出
+        let (queue, mut receiver) = mpsc::unbounded_channel::<String>();
+        runtime.spawn(async move {
+            while let Some(text) = receiver.recv().await {
+                let result = client
+                    .post(&endpoint)
+                    .json(&serde_json::json!({ "text": text }))
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status());
+                if let Err(e) = result {
+                    log::warn!("HTTP 输出失败: {}", e);
+                }
+            }
+        });
+        Ok(Self { queue })
+    }
+}
+
+impl OutputSink for HttpSink {
+    fn commit(&self, text: &str) -> Result<(), String> {
+        self.queue.send(text.to_string()).map_err(|_| "HTTP 输出已停止".to_string())
+    }
+}
@@ -0,0 +1,81 @@
+//! 大模型润色
+//!
+//! 调用 OpenAI 兼容的 chat/completions 接口（流式），逐段回调累积的结果
+
+use crate::models::config::{PostEditConfig, PromptTemplate};
+
+#[derive(serde::Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(serde::Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: Delta,
+}
+
+#[derive(serde::Deserialize, Default)]
+struct Delta {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+fn system_prompt(config: &PostEditConfig) -> String {
+    match config.template {
+        PromptTemplate::CleanUp => "整理用户给出的语音识别文本：补全标点、修正明显的识别错误、删除口头语，不改变原意。只输出整理后的文本。".to_string(),
+        PromptTemplate::Formalize => "把用户给出的口语化文本改写为正式的书面语，适合邮件或文档。只输出改写后的文本。".to_string(),
+        PromptTemplate::Translate => format!("把用户给出的文本翻译为{}。只输出译文。", config.target_language),
+        PromptTemplate::Summarize => "用一句话概括用户给出的文本。只输出概括内容。".to_string(),
+    }
+}
+
+/// 流式润色文本，每收到新内容就以累积结果调用 on_partial，返回最终结果
+pub async fn stream(
+    config: &PostEditConfig,
+    text: &str,
+    mut on_partial: impl FnMut(&str),
+) -> Result<String, String> {
+    let body = serde_json::json!({
+        "model": config.model,
+        "stream": true,
+        "messages": [
+            {"role": "system", "content": system_prompt(config)},
+            {"role": "user", "content": text},
+        ],
+    });
+
+    let mut request = reqwest::Client::new().post(&config.endpoint).json(&body);
+    if let Some(key) = config.api_key.as_deref().filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(key);
+    }
+    let mut response = request
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("润色请求失败: {}", e))?;
+
+    // 按 SSE 行解析: "data: {...}" / "data: [DONE]"
+    let mut result = String::new();
+    let mut line_buf: Vec<u8> = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| format!("润色响应读取失败: {}", e))? {
+        // 按字节缓存，避免多字节字符被分块截断
+        line_buf.extend_from_slice(&chunk);
+        while let Some(pos) = line_buf.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = line_buf.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&line);
+            let Some(data) = line.trim().strip_prefix("data:") else { continue };
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok(result.trim().to_string());
+            }
+            let Ok(chunk) = serde_json::from_str::<StreamChunk>(data) else { continue };
+            if let Some(content) = chunk.choices.first().and_then(|c| c.delta.content.as_deref()) {
+                result.push_str(content);
+                on_partial(result.trim_start());
+            }
+        }
+    }
+
+    Ok(result.trim().to_string())
+}
@@ -0,0 +1,207 @@
+//! 标点风格统一
+//!
+//! 不同服务商返回的标点风格不一致，这里按配置统一：全角/半角标点、中英文间空格、
+//! 句末句号、句首大写
+
+use crate::models::config::{PunctuationConfig, PunctuationStyle, TrailingPeriod};
+
+const FULL: [char; 8] = ['，', '。', '？', '！', '：', '；', '（', '）'];
+const HALF: [char; 8] = [',', '.', '?', '!', ':', ';', '(', ')'];
+
+pub fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' | '\u{3040}'..='\u{30ff}' | '\u{ac00}'..='\u{d7af}')
+}
+
+fn is_sentence_end(c: char) -> bool {
+    matches!(c, '。' | '？' | '！' | '.' | '?' | '!')
+}
+
+/// 统一一段文本的标点风格
+///
+/// context 为该段之前已输出的文本（用于跨段的大写和空格判断），
+/// is_final 为该段是否已定稿（句末句号只在定稿时处理）
+pub fn normalize(text: &str, config: &PunctuationConfig, context: &str, is_final: bool) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+
+    match config.style {
+        PunctuationStyle::FullWidth => to_full_width(&mut chars),
+        PunctuationStyle::HalfWidth => {
+            chars = to_half_width(&chars);
+            // 上一段以半角标点结尾时段首补空格（段内的空格由 to_half_width 补）
+            let prev = context.chars().last();
+            if prev.is_some_and(|p| p != '(' && HALF.contains(&p))
+                && chars.first().is_some_and(|c| !c.is_whitespace() && !HALF.contains(c))
+            {
+                chars.insert(0, ' ');
+            }
+        }
+        PunctuationStyle::Keep => {}
+    }
+    if config.cjk_latin_spacing {
+        chars = add_cjk_latin_spacing(&chars, context.chars().last());
+    }
+    if config.capitalize {
+        capitalize(&mut chars, context);
+    }
+    if is_final {
+        trailing_period(&mut chars, &config.trailing_period);
+    }
+
+    chars.into_iter().collect()
+}
+
+/// 半角标点紧邻中文时转为全角（"3.5"、"e.g." 等不受影响）
+fn to_full_width(chars: &mut [char]) {
+    for i in 0..chars.len() {
+        let Some(pos) = HALF.iter().position(|&h| h == chars[i]) else { continue };
+        let before = i.checked_sub(1).map(|j| chars[j]);
+        let after = chars.get(i + 1).copied();
+        if before.is_some_and(is_cjk) || after.is_some_and(is_cjk) {
+            chars[i] = FULL[pos];
+        }
+    }
+}
+
+/// 全角标点转为半角，句中标点后补空格
+fn to_half_width(chars: &[char]) -> Vec<char> {
+    let mut out = Vec::with_capacity(chars.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let Some(pos) = FULL.iter().position(|&f| f == c) else {
+            out.push(c);
+            continue;
+        };
+        out.push(HALF[pos]);
+        let next = chars.get(i + 1);
+        if c != '（' && next.is_some_and(|n| !n.is_whitespace() && !FULL.contains(n) && !HALF.contains(n)) {
+            out.push(' ');
+        }
+    }
+    out
+}
+
+/// 中文与英文/数字之间加空格
+fn add_cjk_latin_spacing(chars: &[char], prev: Option<char>) -> Vec<char> {
+    let mut out = Vec::with_capacity(chars.len());
+    let mut last = prev;
+    for &c in chars {
+        if let Some(l) = last {
+            if (is_cjk(l) && c.is_ascii_alphanumeric()) || (l.is_ascii_alphanumeric() && is_cjk(c)) {
+                out.push(' ');
+            }
+        }
+        out.push(c);
+        last = Some(c);
+    }
+    out
+}
+
+/// 句首字母大写（英文句点后需有空白才视为句末，避免 "e.g." 之类被误判）
+fn capitalize(chars: &mut [char], context: &str) {
+    let mut at_start = context.trim_end().chars().last().is_none_or(|c| is_sentence_end(c) || c == '\n');
+    let mut after_period = false;
+    for c in chars.iter_mut() {
+        if c.is_whitespace() {
+            at_start |= after_period || *c == '\n';
+        } else if is_sentence_end(*c) {
+            at_start = !c.is_ascii();
+            after_period = c.is_ascii();
+            continue;
+        } else if c.is_alphabetic() {
+            if at_start {
+                *c = c.to_ascii_uppercase();
+            }
+            at_start = false;
+        } else {
+            at_start = false;
+        }
+        after_period = false;
+    }
+}
+
+fn trailing_period(chars: &mut Vec<char>, mode: &TrailingPeriod) {
+    while chars.last().is_some_and(|c| c.is_whitespace()) {
+        chars.pop();
+    }
+    let Some(&last) = chars.last() else { return };
+    match mode {
+        TrailingPeriod::Add if !is_sentence_end(last) && !matches!(last, '，' | ',' | '：' | ':' | '；' | ';') => {
+            chars.push(if is_cjk(last) { '。' } else { '.' });
+        }
+        TrailingPeriod::Remove if matches!(last, '。' | '.') => {
+            chars.pop();
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(style: PunctuationStyle) -> PunctuationConfig {
+        PunctuationConfig { enabled: true, style, ..Default::default() }
+    }
+
+    #[test]
+    fn normalize_table() {
+        let full = config(PunctuationStyle::FullWidth);
+        let half = config(PunctuationStyle::HalfWidth);
+        let spacing = PunctuationConfig { cjk_latin_spacing: true, ..config(PunctuationStyle::Keep) };
+        let capitalize = PunctuationConfig { capitalize: true, ..config(PunctuationStyle::Keep) };
+        let add = PunctuationConfig { trailing_period: TrailingPeriod::Add, ..config(PunctuationStyle::Keep) };
+        let remove = PunctuationConfig { trailing_period: TrailingPeriod::Remove, ..config(PunctuationStyle::Keep) };
+        let cases = [
+            // 全角
+            (&full, "", "你好,世界.", "你好，世界。"),
+            (&full, "", "真的吗?", "真的吗？"),
+            (&full, "", "价格是3.5元", "价格是3.5元"),
+            (&full, "", "e.g. this", "e.g. this"),
+            // 半角
+            (&half, "", "你好，世界。", "你好, 世界."),
+            (&half, "", "注意：重要", "注意: 重要"),
+            (&half, "", "好的。", "好的."),
+            (&half, "好的.", "明天见", " 明天见"),
+            (&half, "你好,", "世界", " 世界"),
+            (&half, "说明(", "见下", "见下"),
+            (&half, "好的. ", "明天见", "明天见"),
+            (&half, "好的.", "。", "."),
+            // 中英文间空格
+            (&spacing, "", "打开VSCode编辑器", "打开 VSCode 编辑器"),
+            (&spacing, "", "共3个", "共 3 个"),
+            (&spacing, "中文", "abc", " abc"),
+            // 句首大写
+            (&capitalize, "", "hello world. this is", "Hello world. This is"),
+            (&capitalize, "", "see e.g.this", "See e.g.this"),
+            (&capitalize, "Done.", " next", " Next"),
+            (&capitalize, "and", " more", " more"),
+            (&capitalize, "你好。", "ok", "Ok"),
+            // 句末句号（只在定稿时处理）
+            (&add, "", "你好", "你好。"),
+            (&add, "", "hello ", "hello."),
+            (&add, "", "你好，", "你好，"),
+            (&add, "", "真的吗？", "真的吗？"),
+            (&remove, "", "你好。", "你好"),
+            (&remove, "", "what?", "what?"),
+        ];
+        for (config, context, input, expected) in cases {
+            assert_eq!(normalize(input, config, context, true), expected, "input: {:?} after {:?}", input, context);
+        }
+    }
+
+    #[test]
+    fn trailing_period_waits_for_final() {
+        let add = PunctuationConfig { trailing_period: TrailingPeriod::Add, ..config(PunctuationStyle::Keep) };
+        assert_eq!(normalize("你好", &add, "", false), "你好");
+    }
+
+    #[test]
+    fn half_width_segments_join_with_space() {
+        let half = config(PunctuationStyle::HalfWidth);
+        let mut committed = String::new();
+        for segment in ["第一句。", "第二句，", "第三句。"] {
+            let text = normalize(segment, &half, &committed, true);
+            committed.push_str(&text);
+        }
+        assert_eq!(committed, "第一句. 第二句, 第三句.");
+    }
+}
@@ -7,6 +7,8 @@
 //! 主入口：
 //! - `toggle()` - 切换录音状态（快捷键 Shift+E 调用）
 //! - `set()`    - 直接设置录音状态（UI按钮或自动停止调用）
+//! - `cancel()` - 取消录音，丢弃尚未输入的识别结果（全局输入监听调用）
+//! - `start_meeting()` / `stop_meeting()` - 开始/结束会议转写
 
 use crate::models::state::AppState;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -55,3 +57,32 @@ pub fn set(state: &AppState, app_handle: &tauri::AppHandle, recording: bool) ->
         log::info!("录音: {}", recording);
     })
 }
+
+pub fn cancel(state: &AppState, app_handle: &tauri::AppHandle) -> bool {
+    try_run(|| {
+        state.is_cancelled.store(true, Ordering::SeqCst);
+        *state.is_recording.lock().unwrap() = false;
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.emit("recording-state-changed", false);
+        }
+        log::info!("录音: 取消");
+    })
+}
+
+/// 开始会议转写（先标记会议模式，再开始录音）
+pub fn start_meeting(state: &AppState, app_handle: &tauri::AppHandle) -> Result<(), String> {
+    if *state.is_recording.lock().unwrap() {
+        return Err("正在录音，请先停止".to_string());
+    }
+    state.is_meeting.store(true, Ordering::SeqCst);
+    if !set(state, app_handle, true) {
+        state.is_meeting.store(false, Ordering::SeqCst);
+        return Err("操作过于频繁，请稍后再试".to_string());
+    }
+    Ok(())
+}
+
+/// 结束会议转写（会议控制器写完剩余结果后清除会议标记）
+pub fn stop_meeting(state: &AppState, app_handle: &tauri::AppHandle) -> bool {
+    state.is_meeting.load(Ordering::SeqCst) && set(state, app_handle, false)
+}
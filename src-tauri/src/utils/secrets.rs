@@ -0,0 +1,121 @@
+//! 密钥存储
+//!
+//! 服务商密钥等敏感配置保存在系统密钥环中，配置文件只保存引用（"secret:名称"）。
+//! 密钥环不可用时（如无桌面环境的 Linux）退回到加密文件 secrets.json，
+//! 加密密钥由机器标识派生，只防止配置文件被直接读取或随配置外传，不防本机恶意程序
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tauri::Manager;
+
+const SERVICE: &str = "voicekit";
+const REF_PREFIX: &str = "secret:";
+const FILE_NAME: &str = "secrets.json";
+
+/// 返回给界面的掩码，提交时原样返回表示不修改
+pub const MASK: &str = "********";
+
+pub fn is_reference(value: &str) -> bool {
+    value.starts_with(REF_PREFIX)
+}
+
+/// 密钥在配置文件中的引用
+pub fn reference(name: &str) -> String {
+    format!("{}{}", REF_PREFIX, name)
+}
+
+/// 保存密钥，返回写入配置的引用
+pub fn store(app: &tauri::AppHandle, name: &str, value: &str) -> Result<String, String> {
+    let keyring = keyring::Entry::new(SERVICE, name).and_then(|e| e.set_password(value));
+    if let Err(e) = keyring {
+        log::warn!("密钥环不可用（{}），使用加密文件保存 {}", e, name);
+        let mut secrets = read_file(app);
+        secrets.insert(name.to_string(), encrypt(value)?);
+        write_file(app, &secrets)?;
+    }
+    Ok(reference(name))
+}
+
+/// 按引用读取密钥
+pub fn resolve(app: &tauri::AppHandle, reference: &str) -> Option<String> {
+    let name = reference.strip_prefix(REF_PREFIX)?;
+    if let Ok(value) = keyring::Entry::new(SERVICE, name).and_then(|e| e.get_password()) {
+        return Some(value);
+    }
+    read_file(app).get(name).and_then(|v| decrypt(v).ok())
+}
+
+/// 删除密钥（密钥环和加密文件中都删除）
+pub fn delete(app: &tauri::AppHandle, name: &str) {
+    if let Ok(entry) = keyring::Entry::new(SERVICE, name) {
+        let _ = entry.delete_credential();
+    }
+    let mut secrets = read_file(app);
+    if secrets.remove(name).is_some() {
+        let _ = write_file(app, &secrets);
+    }
+}
+
+fn file_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    app.path().app_config_dir().ok().map(|d| d.join(FILE_NAME))
+}
+
+fn read_file(app: &tauri::AppHandle) -> BTreeMap<String, String> {
+    file_path(app)
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn write_file(app: &tauri::AppHandle, secrets: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = file_path(app).ok_or("无法获取配置目录")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(secrets).map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("保存密钥文件失败: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+/// 由机器标识和用户名派生文件加密密钥
+fn file_key() -> Aes256Gcm {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|p| std::fs::read_to_string(p).ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .unwrap_or_default();
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+    let key = Sha256::digest(format!("{}:{}:{}", SERVICE, machine_id.trim(), user));
+    Aes256Gcm::new_from_slice(&key).expect("AES-256 key length")
+}
+
+/// 加密为 base64(nonce || 密文)
+fn encrypt(value: &str) -> Result<String, String> {
+    let mut nonce = [0u8; 12];
+    rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut nonce);
+    let cipher = file_key()
+        .encrypt(Nonce::from_slice(&nonce), value.as_bytes())
+        .map_err(|e| format!("加密失败: {}", e))?;
+    Ok(BASE64.encode([nonce.as_slice(), &cipher].concat()))
+}
+
+fn decrypt(value: &str) -> Result<String, String> {
+    let bytes = BASE64.decode(value).map_err(|e| e.to_string())?;
+    if bytes.len() < 12 {
+        return Err("密文过短".to_string());
+    }
+    let (nonce, cipher) = bytes.split_at(12);
+    let plain = file_key()
+        .decrypt(Nonce::from_slice(nonce), cipher)
+        .map_err(|e| format!("解密失败: {}", e))?;
+    String::from_utf8(plain).map_err(|e| e.to_string())
+}
@@ -1,24 +1,59 @@
 //! 快捷键解析工具
 
-use tauri_plugin_global_shortcut::{Code, Modifiers, GlobalShortcutExt};
+use crate::models::config::AppConfig;
+use tauri_plugin_global_shortcut::{Code, Modifiers, GlobalShortcutExt, Shortcut};
 
-/// 注册快捷键（先注销所有，再注册新的）
-pub fn update_shortcut<R: tauri::Runtime>(
-    app: &tauri::AppHandle<R>, 
-    shortcut_str: &str
+/// 快捷键对应的功能
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShortcutAction {
+    ToggleRecording,
+    UndoDictation,
+    CycleLanguage,
+    CycleProfile,
+}
+
+/// 主快捷键之外的附加快捷键（空字符串表示未启用）
+fn extra_bindings(config: &AppConfig) -> Vec<(ShortcutAction, &str)> {
+    [
+        (ShortcutAction::UndoDictation, config.undo_shortcut.as_str()),
+        (ShortcutAction::CycleLanguage, config.language_shortcut.as_str()),
+        (ShortcutAction::CycleProfile, config.profile_shortcut.as_str()),
+    ]
+        .into_iter()
+        .filter(|(_, s)| !s.trim().is_empty())
+        .collect()
+}
+
+/// 查找快捷键对应的功能，未匹配附加快捷键时视为主快捷键
+pub fn action_of(config: &AppConfig, shortcut: &Shortcut) -> ShortcutAction {
+    extra_bindings(config)
+        .into_iter()
+        .find(|(_, s)| parse_shortcut(s).is_ok_and(|sc| sc == *shortcut))
+        .map_or(ShortcutAction::ToggleRecording, |(action, _)| action)
+}
+
+/// 重新注册全部快捷键（先注销所有，再注册主快捷键和附加快捷键）
+pub fn update_shortcuts<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    config: &AppConfig,
 ) -> Result<(), String> {
     // 1. 注销所有已注册的快捷键
     let manager = app.global_shortcut();
     manager.unregister_all()
         .map_err(|e| format!("注销快捷键失败: {}", e))?;
     
-    // 2. 解析新的快捷键
-    let shortcut = parse_shortcut(shortcut_str)?;
-    
-    // 3. 注册新的快捷键
+    // 2. 解析并注册主快捷键
+    let shortcut = parse_shortcut(&config.shortcut)?;
     manager.register(shortcut)
         .map_err(|e| format!("注册快捷键失败: {}", e))?;
     
+    // 3. 注册附加快捷键（被占用等失败时返回错误，由调用方回滚）
+    for (action, s) in extra_bindings(config) {
+        let sc = parse_shortcut(s)?;
+        manager.register(sc)
+            .map_err(|e| format!("注册快捷键 {:?} ({}) 失败: {}", action, s, e))?;
+    }
+    
     Ok(())
 }
 
@@ -33,9 +68,21 @@ pub fn init_shortcut<R: tauri::Runtime>(
     Ok(())
 }
 
+/// 注册附加快捷键（失败仅记录日志，不影响主快捷键）
+pub fn register_extra<R: tauri::Runtime, M: tauri::Manager<R>>(app: &M, config: &AppConfig) {
+    for (action, s) in extra_bindings(config) {
+        let result = parse_shortcut(s).and_then(|sc| {
+            app.global_shortcut().register(sc).map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            log::error!("注册快捷键 {:?} ({}) 失败: {}", action, s, e);
+        }
+    }
+}
+
 /// 将字符串解析为快捷键
 /// 支持格式: "Ctrl+Shift+A", "Alt+F1", "Shift+Space", "Cmd+K" 等
-pub fn parse_shortcut(s: &str) -> Result<tauri_plugin_global_shortcut::Shortcut, String> {
+pub fn parse_shortcut(s: &str) -> Result<Shortcut, String> {
     let parts: Vec<&str> = s.split('+').map(|p| p.trim()).collect();
     
     if parts.len() < 2 {
@@ -63,7 +110,7 @@ pub fn parse_shortcut(s: &str) -> Result<tauri_plugin_global_shortcut::Shortcut,
     // 解析主键
     let code = parse_key_code(main_key)?;
     
-    Ok(tauri_plugin_global_shortcut::Shortcut::new(
+    Ok(Shortcut::new(
         Some(modifiers),
         code,
     ))
@@ -0,0 +1,170 @@
+//! 语音指令识别
+//!
+//! 整句识别结果（去掉首尾空白和句末标点、忽略大小写）等于指令短语时，
+//! 转换为按键动作而不是输入文本
+
+use crate::models::config::{CommandAction, VoiceCommandConfig};
+
+/// 内置指令（中文 + 英文）
+const BUILTIN: &[(&str, CommandAction)] = &[
+    ("换行", CommandAction::NewLine),
+    ("回车", CommandAction::NewLine),
+    ("new line", CommandAction::NewLine),
+    ("press enter", CommandAction::NewLine),
+    ("新段落", CommandAction::NewParagraph),
+    ("另起一段", CommandAction::NewParagraph),
+    ("new paragraph", CommandAction::NewParagraph),
+    ("删除上一个词", CommandAction::DeleteWord),
+    ("delete last word", CommandAction::DeleteWord),
+    ("删除上一句", CommandAction::DeleteSentence),
+    ("delete last sentence", CommandAction::DeleteSentence),
+    ("delete that", CommandAction::DeleteSentence),
+    ("全选", CommandAction::SelectAll),
+    ("select all", CommandAction::SelectAll),
+    ("撤销", CommandAction::Undo),
+    ("undo", CommandAction::Undo),
+    ("停止听写", CommandAction::StopDictation),
+    ("stop dictation", CommandAction::StopDictation),
+];
+
+/// 标点名称
+const PUNCTUATION: &[(&str, &str)] = &[
+    ("逗号", "，"),
+    ("句号", "。"),
+    ("问号", "？"),
+    ("感叹号", "！"),
+    ("冒号", "："),
+    ("分号", "；"),
+    ("顿号", "、"),
+    ("comma", ","),
+    ("period", "."),
+    ("full stop", "."),
+    ("question mark", "?"),
+    ("exclamation mark", "!"),
+    ("colon", ":"),
+    ("semicolon", ";"),
+];
+
+pub struct CommandSet {
+    commands: Vec<(String, CommandAction)>,
+}
+
+impl CommandSet {
+    /// 构建指令表，未启用时返回 None
+    pub fn new(config: &VoiceCommandConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        let custom = config.custom.iter().map(|c| (normalize(&c.phrase), c.action.clone()));
+        let builtin = BUILTIN.iter().map(|(p, a)| (p.to_string(), a.clone()));
+        let punctuation = PUNCTUATION.iter().map(|(p, t)| (p.to_string(), CommandAction::Insert(t.to_string())));
+        Some(Self { commands: custom.chain(builtin).chain(punctuation).collect() })
+    }
+
+    /// 匹配整句指令
+    pub fn match_command(&self, text: &str) -> Option<&CommandAction> {
+        let text = normalize(text);
+        self.commands.iter().find(|(p, _)| *p == text).map(|(_, a)| a)
+    }
+}
+
+fn normalize(text: &str) -> String {
+    text.trim()
+        .trim_end_matches(['。', '.', '！', '!', '？', '?', '，', ','])
+        .trim()
+        .to_lowercase()
+}
+
+/// 删除最后一个词需要的退格数：英文按单词，其他按单个字符（含末尾空白）
+pub fn last_word_len(text: &str) -> usize {
+    let chars: Vec<char> = text.chars().collect();
+    let trimmed = chars.iter().rev().take_while(|c| c.is_whitespace()).count();
+    let rest = &chars[..chars.len() - trimmed];
+    let word = match rest.last() {
+        Some(c) if c.is_ascii_alphanumeric() => rest.iter().rev().take_while(|c| c.is_ascii_alphanumeric()).count(),
+        Some(_) => 1,
+        None => 0,
+    };
+    trimmed + word
+}
+
+/// 删除最后一句需要的退格数：回退到上一个句末标点或换行之后
+pub fn last_sentence_len(text: &str) -> usize {
+    let is_end = |c: &char| matches!(c, '。' | '！' | '？' | '.' | '!' | '?' | '\n');
+    let chars: Vec<char> = text.chars().collect();
+    // 跳过最后一句自身的句末标点
+    let tail = chars.iter().rev().take_while(|c| is_end(c) || c.is_whitespace()).count();
+    let body = chars[..chars.len() - tail].iter().rev().take_while(|c| !is_end(c)).count();
+    tail + body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::config::VoiceCommand;
+
+    fn enabled(custom: Vec<VoiceCommand>) -> CommandSet {
+        CommandSet::new(&VoiceCommandConfig { enabled: true, custom }).unwrap()
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        assert!(CommandSet::new(&VoiceCommandConfig::default()).is_none());
+    }
+
+    #[test]
+    fn match_whole_sentence() {
+        let commands = enabled(Vec::new());
+        assert_eq!(commands.match_command("换行。"), Some(&CommandAction::NewLine));
+        assert_eq!(commands.match_command(" Select All! "), Some(&CommandAction::SelectAll));
+        assert_eq!(commands.match_command("逗号"), Some(&CommandAction::Insert("，".to_string())));
+        assert_eq!(commands.match_command("full stop."), Some(&CommandAction::Insert(".".to_string())));
+        // 指令只在整句时生效
+        assert_eq!(commands.match_command("换行吧"), None);
+        assert_eq!(commands.match_command("请全选"), None);
+        assert_eq!(commands.match_command(""), None);
+    }
+
+    #[test]
+    fn custom_commands_take_priority() {
+        let commands = enabled(vec![
+            VoiceCommand { phrase: "全选".to_string(), action: CommandAction::NewLine },
+            VoiceCommand { phrase: " Ship It。".to_string(), action: CommandAction::Insert("🚀".to_string()) },
+        ]);
+        assert_eq!(commands.match_command("全选"), Some(&CommandAction::NewLine));
+        assert_eq!(commands.match_command("ship it"), Some(&CommandAction::Insert("🚀".to_string())));
+        assert_eq!(commands.match_command("撤销"), Some(&CommandAction::Undo));
+    }
+
+    #[test]
+    fn last_word_len_table() {
+        let cases = [
+            ("hello world", 5),
+            ("hello world  ", 7),
+            ("version 2", 1),
+            ("你好", 1),
+            ("abc, ", 2),
+            ("   ", 3),
+            ("", 0),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(last_word_len(text), expected, "{:?}", text);
+        }
+    }
+
+    #[test]
+    fn last_sentence_len_table() {
+        let cases = [
+            ("第一句。第二句。", 4),
+            ("第一句。第二句", 3),
+            ("Hello. World", 6),
+            ("Hello. World! ", 8),
+            ("第一行\n第二行", 3),
+            ("only one", 8),
+            ("", 0),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(last_sentence_len(text), expected, "{:?}", text);
+        }
+    }
+}
@@ -0,0 +1,65 @@
+//! 文本替换规则引擎
+//!
+//! 规则作用于整句识别文本（而非单个字符），结果再交给 compute_diff 计算增量
+
+use crate::models::config::ReplaceRule;
+use regex::{NoExpand, Regex, RegexBuilder};
+
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    literal: bool,
+}
+
+/// 编译后的规则集
+pub struct RuleSet {
+    rules: Vec<CompiledRule>,
+}
+
+impl RuleSet {
+    /// 编译适用于指定应用的规则（无效正则跳过并记录日志）
+    pub fn compile(rules: &[ReplaceRule], app: Option<&str>) -> Self {
+        if app.is_none() && rules.iter().any(|r| r.enabled && !r.apps.is_empty()) {
+            log::info!("无法获取前台应用（仅支持 Windows），限定应用的替换规则本次不生效");
+        }
+        let rules = rules
+            .iter()
+            .filter(|r| r.enabled && !r.pattern.is_empty())
+            .filter(|r| r.apps.is_empty() || app.is_some_and(|a| r.apps.iter().any(|x| x.eq_ignore_ascii_case(a))))
+            .filter_map(|r| match compile_rule(r) {
+                Ok(regex) => Some(CompiledRule {
+                    regex,
+                    replacement: r.replacement.clone(),
+                    literal: !r.regex,
+                }),
+                Err(e) => {
+                    log::warn!("替换规则 {:?} 无效: {}", r.pattern, e);
+                    None
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// 按顺序应用所有规则
+    pub fn apply(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for rule in &self.rules {
+            text = if rule.literal {
+                rule.regex.replace_all(&text, NoExpand(&rule.replacement)).into_owned()
+            } else {
+                rule.regex.replace_all(&text, rule.replacement.as_str()).into_owned()
+            };
+        }
+        text
+    }
+}
+
+/// 编译单条规则（字面文本先转义）
+pub fn compile_rule(rule: &ReplaceRule) -> Result<Regex, String> {
+    let pattern = if rule.regex { rule.pattern.clone() } else { regex::escape(&rule.pattern) };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!rule.case_sensitive)
+        .build()
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,37 @@
+//! 翻译
+//!
+//! 调用本地翻译服务（LibreTranslate 兼容的 /translate 接口），只用于已定稿的句子
+
+use crate::models::config::TranslationConfig;
+
+#[derive(serde::Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+/// 翻译一段文本
+pub async fn translate(config: &TranslationConfig, text: &str) -> Result<String, String> {
+    let mut body = serde_json::json!({
+        "q": text,
+        "source": config.source_language,
+        "target": config.target_language,
+        "format": "text",
+    });
+    if let Some(key) = config.api_key.as_deref().filter(|k| !k.is_empty()) {
+        body["api_key"] = key.into();
+    }
+
+    let response: TranslateResponse = reqwest::Client::new()
+        .post(&config.endpoint)
+        .json(&body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("翻译请求失败: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("翻译响应解析失败: {}", e))?;
+
+    Ok(response.translated_text.trim().to_string())
+}
@@ -0,0 +1,86 @@
+//! 热词后处理
+//!
+//! 供热词不带权重的服务商使用（由 Transcript 在识别后调用）：把识别文本中与热词仅大小写或空格不同的片段替换为热词原文，
+//! 例如 "git hub" → "GitHub"
+
+use crate::models::config::HotWord;
+
+/// 对识别文本应用热词替换（权重高、长度长的热词优先）
+pub fn apply(text: &str, words: &[HotWord]) -> String {
+    let mut sorted: Vec<&HotWord> = words.iter().filter(|w| !w.word.trim().is_empty()).collect();
+    sorted.sort_by_key(|w| (std::cmp::Reverse(w.weight.unwrap_or(0)), std::cmp::Reverse(w.word.chars().count())));
+
+    let mut chars: Vec<char> = text.chars().collect();
+    for hw in sorted {
+        let target: Vec<char> = hw.word.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut out = Vec::with_capacity(chars.len());
+        let mut i = 0;
+        while i < chars.len() {
+            match match_at(&chars, i, &target) {
+                Some(end) => {
+                    out.extend(hw.word.chars());
+                    i = end;
+                }
+                None => {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+        }
+        chars = out;
+    }
+    chars.into_iter().collect()
+}
+
+/// 从 start 开始匹配热词（忽略大小写和词内空格），返回匹配结束位置
+fn match_at(text: &[char], start: usize, target: &[char]) -> Option<usize> {
+    let is_word = |c: char| c.is_ascii_alphanumeric();
+    if start > 0 && is_word(text[start - 1]) && target.first().is_some_and(|&c| is_word(c)) {
+        return None;
+    }
+
+    let mut i = start;
+    for (k, &t) in target.iter().enumerate() {
+        if k > 0 {
+            while i < text.len() && text[i] == ' ' {
+                i += 1;
+            }
+        }
+        if i >= text.len() || !text[i].to_lowercase().eq(t.to_lowercase()) {
+            return None;
+        }
+        i += 1;
+    }
+
+    if i < text.len() && is_word(text[i]) && target.last().is_some_and(|&c| is_word(c)) {
+        return None;
+    }
+    Some(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(list: &[&str]) -> Vec<HotWord> {
+        list.iter().map(|w| HotWord { word: w.to_string(), weight: None }).collect()
+    }
+
+    #[test]
+    fn apply_table() {
+        let cases: &[(&str, &[&str], &str)] = &[
+            ("i use git hub daily", &["GitHub"], "i use GitHub daily"),
+            ("打开 vscode", &["VSCode"], "打开 VSCode"),
+            ("VS code 很好用", &["VSCode"], "VSCode 很好用"),
+            ("github", &["Git", "GitHub"], "GitHub"),
+            ("mygithub", &["GitHub"], "mygithub"),
+            ("githubs", &["GitHub"], "githubs"),
+            ("打开微信", &["微信"], "打开微信"),
+            ("no change", &[], "no change"),
+            ("no change", &["", "  "], "no change"),
+        ];
+        for (input, list, expected) in cases {
+            assert_eq!(apply(input, &words(list)), *expected, "input: {}", input);
+        }
+    }
+}
@@ -49,3 +49,50 @@ pub fn hide(app: &AppHandle, label: &str) {
         let _ = window.hide();
     }
 }
+
+/// 获取当前前台窗口句柄（非 Windows 平台返回 None）
+pub fn foreground_window() -> Option<isize> {
+    #[cfg(windows)]
+    unsafe {
+        let hwnd = windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow();
+        if hwnd.0.is_null() { None } else { Some(hwnd.0 as isize) }
+    }
+    
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// 获取当前前台应用的进程名（如 "code.exe"，非 Windows 平台返回 None）
+///
+/// 按应用限定的替换规则和配置方案依赖此函数，因此在 macOS / Linux 上不会生效
+pub fn foreground_app() -> Option<String> {
+    #[cfg(windows)]
+    unsafe {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::{
+            OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION
+        };
+        use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
+        
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut pid));
+        if pid == 0 { return None; }
+        
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 260];
+        let mut len = buf.len() as u32;
+        let result = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, windows::core::PWSTR(buf.as_mut_ptr()), &mut len);
+        let _ = CloseHandle(process);
+        result.ok()?;
+        
+        let path = String::from_utf16_lossy(&buf[..len as usize]);
+        path.rsplit('\\').next().map(|name| name.to_lowercase())
+    }
+    
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
@@ -1,36 +1,52 @@
 use crate::asr::manager::AsrProvider;
 use crate::models::state::AppState;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::thread;
 use std::time::Duration;
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
 
 /// ASR 控制器
 /// 
 /// 监控录音状态，开始录音时启动 ASR，停止录音时停止 ASR
-pub fn init_asr_controller(app_state: Arc<AppState>) {
+pub fn init_asr_controller(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
     thread::spawn(move || {
         let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
-        rt.block_on(run_asr_loop(app_state));
+        rt.block_on(run_asr_loop(app_state, app_handle));
     });
     
     log::info!("ASR 控制器已启动");
 }
 
-async fn run_asr_loop(app_state: Arc<AppState>) {
+async fn run_asr_loop(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
     let mut was_recording = false;
     let mut current_provider: Option<AsrProvider> = None;
     
     loop {
+        // 语音指令要求停止听写：防抖忽略了本次操作时保留请求，下一轮重试
+        if app_state.asr_manager.stop_requested() {
+            let stopped = !*app_state.is_recording.lock().unwrap()
+                || crate::utils::recording_state::set(&app_state, &app_handle, false);
+            if stopped {
+                app_state.asr_manager.clear_stop_request();
+            }
+        }
+        
         let is_recording = *app_state.is_recording.lock().unwrap();
         
-        // 开始录音：启动 ASR
-        if is_recording && !was_recording {
+        // 开始录音：启动 ASR（会议模式由会议控制器处理）
+        if is_recording && !was_recording && !app_state.is_meeting.load(Ordering::SeqCst) {
             log::info!("ASR: 检测到录音开始，启动 ASR");
+            app_state.is_cancelled.store(false, Ordering::SeqCst);
+            app_state.inserted_chars.store(0, Ordering::SeqCst);
+            *app_state.dictation_window.lock().unwrap() = crate::utils::window_ext::foreground_window();
             
             match app_state.asr_manager.create_provider() {
                 Ok(provider) => {
                     if let Err(e) = provider.start().await {
                         log::error!("ASR 启动失败: {}", e);
+                        report_start_error(&app_handle, &e);
                     } else {
                         current_provider = Some(provider);
                         log::info!("ASR 已启动");
@@ -38,12 +54,32 @@ async fn run_asr_loop(app_state: Arc<AppState>) {
                 }
                 Err(e) => {
                     log::error!("创建 ASR Provider 失败: {}", e);
+                    report_start_error(&app_handle, &e);
                 }
             }
             
             was_recording = true;
         }
         
+        // 取消录音：中止 ASR，丢弃未输入的结果
+        if !is_recording && was_recording && app_state.is_cancelled.swap(false, Ordering::SeqCst) {
+            log::info!("ASR: 检测到录音取消，中止 ASR");
+            
+            if let Some(provider) = current_provider.take() {
+                provider.cancel().await;
+            }
+            app_state.audio_buffer.clear();
+            app_state.audio_buffer.finish();
+            app_state.text_buffer.clear();
+            
+            if app_state.config.lock().unwrap().cancel.erase_inserted {
+                let inserted = app_state.inserted_chars.load(Ordering::SeqCst);
+                app_state.text_buffer.push_backspaces(inserted);
+            }
+            
+            was_recording = false;
+        }
+        
         // 停止录音：停止 ASR
         if !is_recording && was_recording {
             log::info!("ASR: 检测到录音停止，停止 ASR");
@@ -61,3 +97,14 @@ async fn run_asr_loop(app_state: Arc<AppState>) {
         thread::sleep(Duration::from_millis(50));
     }
 }
+
+/// 通知界面识别未能开始（如缺少服务商凭据），录音窗口和系统通知均可见
+pub fn report_start_error(app_handle: &tauri::AppHandle, message: &str) {
+    let _ = app_handle.emit("asr-start-failed", message);
+    let _ = app_handle
+        .notification()
+        .builder()
+        .title("语音识别未能开始")
+        .body(message)
+        .show();
+}
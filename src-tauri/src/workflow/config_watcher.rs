@@ -0,0 +1,87 @@
+use crate::models::config::AppConfig;
+use crate::models::state::AppState;
+use crate::models::validation::ConfigError;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// 检查配置文件修改时间的间隔
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// 修改时间稳定这么久后才读取，避免读到写了一半的文件
+const SETTLE: Duration = Duration::from_millis(300);
+
+/// 配置文件监视
+///
+/// 轮询 config.json 的修改时间，外部修改（手动编辑、dotfile 管理工具）后重新校验并实时应用；
+/// 无效的修改不生效并弹出通知。应用自身保存引起的变化与当前配置一致，会被忽略
+pub fn init(app_handle: tauri::AppHandle) {
+    let path = match app_handle.path().app_data_dir() {
+        Ok(dir) => dir.join("config.json"),
+        Err(e) => {
+            log::error!("获取配置文件路径失败，不监视配置文件: {}", e);
+            return;
+        }
+    };
+
+    thread::spawn(move || {
+        let mut last = modified(&path);
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = modified(&path);
+            if current == last {
+                continue;
+            }
+            thread::sleep(SETTLE);
+            if modified(&path) != current {
+                continue;
+            }
+            last = current;
+            reload(&app_handle, &path);
+        }
+    });
+
+    log::info!("配置文件监视已启动");
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn reload(app: &tauri::AppHandle, path: &Path) {
+    let state = app.state::<AppState>();
+    let result = read(app, path).and_then(|mut config| {
+        let mut current = state.config.lock().unwrap().clone();
+        // 密钥环暂时无法读取时沿用已读取的密钥，避免保存时丢失或误报凭据缺失
+        config.fill_unresolved(&current);
+        // 版本号只在保存时写入，比较时忽略
+        current.version = config.version;
+        if serde_json::to_value(&config).ok() == serde_json::to_value(&current).ok() {
+            return Ok(false);
+        }
+        crate::commands::settings::apply_config(app, &state, config).map(|_| true)
+    });
+
+    match result {
+        Ok(true) => log::info!("已应用配置文件的修改"),
+        Ok(false) => {}
+        Err(e) => {
+            log::warn!("配置文件的修改无效，未应用: {}", e);
+            let _ = app.emit("config-reload-failed", &e);
+            let _ = app
+                .notification()
+                .builder()
+                .title("配置文件无效，修改未生效")
+                .body(e.to_string())
+                .show();
+        }
+    }
+}
+
+fn read(app: &tauri::AppHandle, path: &Path) -> Result<AppConfig, ConfigError> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("读取配置文件失败: {}", e))?;
+    let doc: serde_json::Value = serde_json::from_str(&content).map_err(|e| format!("配置文件格式错误: {}", e))?;
+    let raw = doc.get("config").cloned().ok_or("配置文件缺少 config 字段".to_string())?;
+    AppConfig::parse_external(app, raw)
+}
@@ -0,0 +1,264 @@
+use crate::asr::transcript::TranscriptEvent;
+use crate::models::state::AppState;
+use crate::utils::export::Session;
+use crate::utils::recording_state;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{broadcast, oneshot};
+
+/// 检查接口开关和端口变化的间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 本地控制接口
+///
+/// 开启后在 127.0.0.1 上提供 REST 接口和 WebSocket 事件流，供其他工具控制听写；
+/// 所有请求须携带访问令牌。开关和端口修改后自动重启服务，令牌修改后立即生效
+///
+/// - GET  /status              录音状态，以及按前台应用生效的服务商和配置方案
+/// - POST /dictation/start     开始听写
+/// - POST /dictation/stop      停止听写
+/// - POST /dictation/cancel    取消听写（丢弃未输入的结果）
+/// - POST /provider            切换服务商 {"provider": "xunfei"}
+/// - POST /profile             切换配置方案 {"name": "coding"}（空字符串为全局配置）
+/// - GET  /transcript/last     最近一次听写的定稿句子
+/// - GET  /events              WebSocket，推送 partial / final 识别结果
+pub fn init(app_handle: AppHandle) {
+    thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+        rt.block_on(run(app_handle));
+    });
+
+    log::info!("控制接口监控已启动");
+}
+
+async fn run(app: AppHandle) {
+    // 正在运行的端口和停止信号
+    let mut running: Option<(u16, oneshot::Sender<()>)> = None;
+    // 启动失败的端口，配置变化前不再重试
+    let mut failed: Option<u16> = None;
+
+    loop {
+        let wanted = {
+            let state = app.state::<AppState>();
+            let config = state.config.lock().unwrap();
+            config.api.enabled.then_some(config.api.port)
+        };
+
+        if running.as_ref().map(|(port, _)| *port) != wanted {
+            if let Some((port, stop)) = running.take() {
+                let _ = stop.send(());
+                log::info!("控制接口已停止: {}", port);
+            }
+            if let Some(port) = wanted.filter(|port| failed != Some(*port)) {
+                match serve(app.clone(), port).await {
+                    Ok(stop) => {
+                        log::info!("控制接口已启动: http://127.0.0.1:{}", port);
+                        running = Some((port, stop));
+                        failed = None;
+                    }
+                    Err(e) => {
+                        log::error!("{}", e);
+                        failed = Some(port);
+                    }
+                }
+            }
+        }
+        if wanted.is_none() {
+            failed = None;
+        }
+
+        tokio::time::sleep(CHECK_INTERVAL).await;
+    }
+}
+
+async fn serve(app: AppHandle, port: u16) -> Result<oneshot::Sender<()>, String> {
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("控制接口监听端口 {} 失败: {}", port, e))?;
+
+    let router = Router::new()
+        .route("/status", get(status))
+        .route("/dictation/start", post(start))
+        .route("/dictation/stop", post(stop))
+        .route("/dictation/cancel", post(cancel))
+        .route("/provider", post(switch_provider))
+        .route("/profile", post(switch_profile))
+        .route("/transcript/last", get(last_transcript))
+        .route("/events", get(events))
+        .layer(middleware::from_fn_with_state(app.clone(), auth))
+        .with_state(app);
+
+    let (tx, rx) = oneshot::channel::<()>();
+    tokio::spawn(async move {
+        let shutdown = async {
+            let _ = rx.await;
+        };
+        if let Err(e) = axum::serve(listener, router).with_graceful_shutdown(shutdown).await {
+            log::error!("控制接口异常退出: {}", e);
+        }
+    });
+    Ok(tx)
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+/// 校验访问令牌（未设置令牌时拒绝所有请求）
+async fn auth(State(app): State<AppHandle>, request: Request, next: Next) -> Response {
+    let expected = app.state::<AppState>().config.lock().unwrap().api.token.clone().unwrap_or_default();
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(|v| v.trim().to_string());
+    let query = request.uri().query().and_then(|q| {
+        q.split('&')
+            .find_map(|pair| pair.strip_prefix("token="))
+            .and_then(|v| urlencoding::decode(v).ok())
+            .map(|v| v.into_owned())
+    });
+
+    match bearer.or(query) {
+        Some(token) if !expected.is_empty() && token_eq(&token, &expected) => next.run(request).await,
+        _ => ApiError(StatusCode::UNAUTHORIZED, "访问令牌无效".to_string()).into_response(),
+    }
+}
+
+/// 比较令牌，耗时与不同字节的位置无关
+fn token_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Serialize)]
+struct Status {
+    recording: bool,
+    meeting: bool,
+    provider: String,
+    profile: String,
+}
+
+async fn status(State(app): State<AppHandle>) -> Json<Status> {
+    status_of(&app)
+}
+
+fn status_of(app: &AppHandle) -> Json<Status> {
+    let state = app.state::<AppState>();
+    let config = state.config.lock().unwrap();
+    // 与开始录音时一致：前台应用匹配的方案优先
+    let app_name = crate::utils::window_ext::foreground_app();
+    let profile = config.profile_for(app_name.as_deref());
+    Json(Status {
+        recording: *state.is_recording.lock().unwrap(),
+        meeting: state.is_meeting.load(Ordering::SeqCst),
+        provider: profile.and_then(|p| p.provider.clone()).unwrap_or_else(|| config.asr.provider.clone()),
+        profile: profile.map(|p| p.name.clone()).unwrap_or_default(),
+    })
+}
+
+/// 设置录音状态（与界面按钮相同，200ms 内的重复操作被忽略）
+fn set_recording(app: &AppHandle, recording: bool) -> ApiResult<Status> {
+    let state = app.state::<AppState>();
+    if state.is_meeting.load(Ordering::SeqCst) {
+        return Err(ApiError(StatusCode::CONFLICT, "正在会议转写".to_string()));
+    }
+    if !recording_state::set(&state, app, recording) {
+        return Err(ApiError(StatusCode::TOO_MANY_REQUESTS, "操作过于频繁，请稍后再试".to_string()));
+    }
+    Ok(status_of(app))
+}
+
+async fn start(State(app): State<AppHandle>) -> ApiResult<Status> {
+    set_recording(&app, true)
+}
+
+async fn stop(State(app): State<AppHandle>) -> ApiResult<Status> {
+    set_recording(&app, false)
+}
+
+async fn cancel(State(app): State<AppHandle>) -> ApiResult<Status> {
+    let state = app.state::<AppState>();
+    if !recording_state::cancel(&state, &app) {
+        return Err(ApiError(StatusCode::TOO_MANY_REQUESTS, "操作过于频繁，请稍后再试".to_string()));
+    }
+    Ok(status_of(&app))
+}
+
+#[derive(Deserialize)]
+struct ProviderRequest {
+    provider: String,
+}
+
+/// 切换全局服务商（下次录音生效）
+async fn switch_provider(State(app): State<AppHandle>, Json(body): Json<ProviderRequest>) -> ApiResult<Status> {
+    let state = app.state::<AppState>();
+    let mut config = state.config.lock().unwrap().clone();
+    config.asr.provider = body.provider;
+    crate::commands::settings::apply_config(&app, &state, config)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e.to_string()))?;
+    Ok(status_of(&app))
+}
+
+#[derive(Deserialize)]
+struct ProfileRequest {
+    name: String,
+}
+
+async fn switch_profile(State(app): State<AppHandle>, Json(body): Json<ProfileRequest>) -> ApiResult<Status> {
+    crate::commands::settings::switch_profile(app.clone(), app.state::<AppState>(), body.name)
+        .map_err(|e| ApiError(StatusCode::BAD_REQUEST, e))?;
+    Ok(status_of(&app))
+}
+
+async fn last_transcript(State(app): State<AppHandle>) -> Json<Session> {
+    Json(app.state::<AppState>().asr_manager.last_session())
+}
+
+async fn events(State(app): State<AppHandle>, ws: WebSocketUpgrade) -> Response {
+    let receiver = app.state::<AppState>().asr_manager.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, receiver))
+}
+
+/// 推送识别结果，客户端断开后结束
+async fn forward_events(mut socket: WebSocket, mut receiver: broadcast::Receiver<TranscriptEvent>) {
+    loop {
+        let received = tokio::select! {
+            received = receiver.recv() => received,
+            // 客户端不发送数据，收到关闭帧、出错或连接断开时结束
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        };
+        let event = match received {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                log::warn!("控制接口事件订阅者处理过慢，丢弃 {} 条事件", skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        let Ok(text) = serde_json::to_string(&event) else { continue };
+        if socket.send(Message::Text(text)).await.is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,340 @@
+//! 本地 FunASR 服务进程管理
+//!
+//! 按配置启动服务进程（一键包目录 + 启动命令），输出写入日志文件；
+//! 定时对 ws://host:port/ws/asr 做 WebSocket 握手检查服务是否响应，进程退出或持续无响应时
+//! 按退避间隔重启，应用退出时停止
+
+use crate::models::config::AppConfig;
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio_tungstenite::tungstenite;
+
+const TICK: Duration = Duration::from_millis(500);
+const HEALTH_INTERVAL: Duration = Duration::from_secs(5);
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(2);
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// 连续健康运行超过该时长后重置退避间隔
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+/// 启动后超过该时长端口仍未就绪视为启动失败（首次加载模型较慢）
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+/// 运行中连续这么多次健康检查失败视为无响应
+const MAX_FAILURES: u32 = 3;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// 服务状态: stopped / starting（已启动进程，端口未就绪）/ running / crashed（等待重启）
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ServiceStatus {
+    #[default]
+    Stopped,
+    Starting,
+    Running,
+    Crashed,
+}
+
+struct Inner {
+    child: Option<Child>,
+    /// 用户要求服务保持运行
+    wanted: bool,
+    status: ServiceStatus,
+    backoff: Duration,
+    restart_at: Option<Instant>,
+    healthy_since: Option<Instant>,
+    last_check: Option<Instant>,
+    started_at: Option<Instant>,
+    /// 就绪后连续失败的健康检查次数
+    failures: u32,
+}
+
+#[derive(Clone)]
+pub struct FunasrService {
+    inner: Arc<Mutex<Inner>>,
+    config: Arc<Mutex<AppConfig>>,
+    log_path: Option<PathBuf>,
+    app_handle: tauri::AppHandle,
+}
+
+impl FunasrService {
+    pub fn new(config: Arc<Mutex<AppConfig>>, app_handle: tauri::AppHandle, log_path: Option<PathBuf>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Inner {
+                child: None,
+                wanted: false,
+                status: ServiceStatus::Stopped,
+                backoff: MIN_BACKOFF,
+                restart_at: None,
+                healthy_since: None,
+                last_check: None,
+                started_at: None,
+                failures: 0,
+            })),
+            config,
+            log_path,
+            app_handle,
+        }
+    }
+
+    pub fn status(&self) -> ServiceStatus {
+        self.inner.lock().unwrap().status
+    }
+
+    /// 启动服务（已在运行时不做处理）
+    pub fn start(&self) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.wanted = true;
+        inner.backoff = MIN_BACKOFF;
+        if inner.child.is_some() {
+            return Ok(());
+        }
+        let result = self.spawn(&mut inner);
+        if result.is_err() {
+            inner.wanted = false;
+        }
+        result
+    }
+
+    /// 停止服务，不再自动重启
+    pub fn stop(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.wanted = false;
+        inner.restart_at = None;
+        if let Some(mut child) = inner.child.take() {
+            log::info!("停止 FunASR 服务 (pid={})", child.id());
+            kill(&mut child);
+        }
+        self.set_status(&mut inner, ServiceStatus::Stopped);
+    }
+
+    fn spawn(&self, inner: &mut Inner) -> Result<(), String> {
+        let service = self.config.lock().unwrap().asr.funasr.service.clone();
+        if service.command.trim().is_empty() {
+            return Err("未配置 FunASR 启动命令".to_string());
+        }
+
+        let folder = Path::new(&service.folder);
+        let program = folder.join(&service.command);
+        let program = if !service.folder.is_empty() && program.exists() { program } else { PathBuf::from(&service.command) };
+        let mut command = Command::new(&program);
+        command
+            .args(&service.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if !service.folder.is_empty() {
+            command.current_dir(folder);
+        }
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            command.creation_flags(CREATE_NO_WINDOW);
+        }
+        // 独立进程组，停止时连同脚本启动的子进程一起结束
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|e| format!("启动 FunASR 服务失败 ({}): {}", program.display(), e))?;
+        log::info!("FunASR 服务已启动 (pid={})", child.id());
+
+        if let Some(stdout) = child.stdout.take() {
+            self.pipe_to_log(stdout, "stdout");
+        }
+        if let Some(stderr) = child.stderr.take() {
+            self.pipe_to_log(stderr, "stderr");
+        }
+
+        inner.child = Some(child);
+        inner.restart_at = None;
+        inner.healthy_since = None;
+        inner.last_check = None;
+        inner.started_at = Some(Instant::now());
+        inner.failures = 0;
+        self.set_status(inner, ServiceStatus::Starting);
+        Ok(())
+    }
+
+    /// 逐行转存进程输出到日志文件
+    fn pipe_to_log(&self, stream: impl Read + Send + 'static, name: &'static str) {
+        let log_path = self.log_path.clone();
+        thread::spawn(move || {
+            let mut file = log_path.and_then(|p| {
+                std::fs::OpenOptions::new().create(true).append(true).open(p).ok()
+            });
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else { break };
+                log::debug!("[FunASR {}] {}", name, line);
+                if let Some(f) = file.as_mut() {
+                    let _ = writeln!(f, "[{}] {}", name, line);
+                }
+            }
+        });
+    }
+
+    fn set_status(&self, inner: &mut Inner, status: ServiceStatus) {
+        if inner.status != status {
+            inner.status = status;
+            let _ = self.app_handle.emit("funasr-service-status", status);
+        }
+    }
+
+    /// 监控循环：检测进程退出、按退避重启、健康检查
+    fn tick(&self) {
+        let now = Instant::now();
+        let check = {
+            let mut inner = self.inner.lock().unwrap();
+            if !inner.wanted {
+                return;
+            }
+
+            let exited = inner.child.as_mut().and_then(|c| c.try_wait().ok().flatten());
+            if let Some(exit) = exited {
+                log::warn!("FunASR 服务退出: {}，{:?} 后重启", exit, inner.backoff);
+                inner.child = None;
+                self.schedule_restart(&mut inner, now);
+            }
+
+            if inner.child.is_none() {
+                if inner.restart_at.is_some_and(|t| now >= t) {
+                    if let Err(e) = self.spawn(&mut inner) {
+                        log::error!("{}", e);
+                        inner.restart_at = Some(now + inner.backoff);
+                        inner.backoff = (inner.backoff * 2).min(MAX_BACKOFF);
+                    }
+                }
+                return;
+            }
+
+            let due = inner.last_check.is_none_or(|t| now.duration_since(t) >= HEALTH_INTERVAL);
+            if due {
+                inner.last_check = Some(now);
+            }
+            due
+        };
+        if !check {
+            return;
+        }
+
+        // 健康检查不持锁，避免阻塞状态查询
+        let (host, port) = {
+            let config = self.config.lock().unwrap();
+            (config.asr.funasr.host.clone(), config.asr.funasr.port)
+        };
+        let healthy = health_check(&host, port);
+
+        let mut inner = self.inner.lock().unwrap();
+        if inner.child.is_none() {
+            return;
+        }
+        if healthy {
+            let since = *inner.healthy_since.get_or_insert(now);
+            if now.duration_since(since) >= STABLE_AFTER {
+                inner.backoff = MIN_BACKOFF;
+            }
+            inner.failures = 0;
+            self.set_status(&mut inner, ServiceStatus::Running);
+            return;
+        }
+
+        // 进程仍在但不响应：就绪后连续失败或启动超时都结束进程并按退避重启
+        let was_ready = inner.status == ServiceStatus::Running || inner.failures > 0;
+        inner.healthy_since = None;
+        let unresponsive = if was_ready {
+            inner.failures += 1;
+            inner.failures >= MAX_FAILURES
+        } else {
+            inner.started_at.is_some_and(|t| now.duration_since(t) >= STARTUP_TIMEOUT)
+        };
+        if unresponsive {
+            log::warn!("FunASR 服务无响应，{:?} 后重启", inner.backoff);
+            if let Some(mut child) = inner.child.take() {
+                kill(&mut child);
+            }
+            self.schedule_restart(&mut inner, now);
+        } else if !was_ready {
+            self.set_status(&mut inner, ServiceStatus::Starting);
+        }
+    }
+
+    fn schedule_restart(&self, inner: &mut Inner, now: Instant) {
+        inner.restart_at = Some(now + inner.backoff);
+        inner.backoff = (inner.backoff * 2).min(MAX_BACKOFF);
+        self.set_status(inner, ServiceStatus::Crashed);
+    }
+}
+
+/// 对识别接口做一次 WebSocket 握手后关闭（不发送识别配置，服务端不会开始识别）
+///
+/// 进程卡死但仍占用端口时 TCP 可以连接，握手会超时
+fn health_check(host: &str, port: u16) -> bool {
+    let Ok(addrs) = (host, port).to_socket_addrs() else {
+        return false;
+    };
+    let Some(stream) = addrs.into_iter().find_map(|addr| TcpStream::connect_timeout(&addr, HEALTH_TIMEOUT).ok()) else {
+        return false;
+    };
+    if stream.set_read_timeout(Some(HEALTH_TIMEOUT)).is_err() || stream.set_write_timeout(Some(HEALTH_TIMEOUT)).is_err() {
+        return false;
+    }
+    match tungstenite::client(format!("ws://{}:{}/ws/asr", host, port), stream) {
+        Ok((mut ws, _)) => {
+            let _ = ws.close(None);
+            let _ = ws.flush();
+            true
+        }
+        Err(e) => {
+            log::debug!("FunASR 健康检查失败: {}", e);
+            false
+        }
+    }
+}
+
+/// 结束进程及其子进程（一键包的启动脚本会再启动 python）
+fn kill(child: &mut Child) {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        let _ = Command::new("taskkill")
+            .args(["/T", "/F", "/PID", &child.id().to_string()])
+            .creation_flags(CREATE_NO_WINDOW)
+            .status();
+    }
+    // 启动时为其创建了独立进程组，进程组号即 pid
+    #[cfg(unix)]
+    {
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{}", child.id())])
+            .status();
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// 启动监控线程，配置了自动启动时随应用启动服务
+pub fn init(service: FunasrService) {
+    if service.config.lock().unwrap().asr.funasr.service.auto_start {
+        if let Err(e) = service.start() {
+            log::error!("{}", e);
+        }
+    }
+
+    thread::spawn(move || loop {
+        service.tick();
+        thread::sleep(TICK);
+    });
+
+    log::info!("FunASR 服务监控已启动");
+}
@@ -1,5 +1,6 @@
 //! 全局输入监听
 
+use crate::models::config::CancelKey;
 use crate::models::state::AppState;
 use rdev::{listen, EventType, Key};
 use std::sync::atomic::Ordering;
@@ -54,7 +55,16 @@ fn is_point_in_window(window: &tauri::WebviewWindow, x: i32, y: i32) -> bool {
     false
 }
 
-/// 启动全局输入监听（录音状态下任意输入取消录音）
+/// 按键是否触发取消
+fn is_cancel_key(trigger: &CancelKey, key: &Key) -> bool {
+    match trigger {
+        CancelKey::Any => !is_modifier_only(key),
+        CancelKey::Escape => matches!(key, Key::Escape),
+        CancelKey::None => false,
+    }
+}
+
+/// 启动全局输入监听（录音状态下按配置的输入取消录音）
 pub fn init(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
     thread::spawn(move || {
         log::info!("全局输入监听已启动");
@@ -70,13 +80,20 @@ pub fn init(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
                 return;
             }
 
-            // 检测到非修饰键的键盘输入或鼠标输入，取消录音
+            // 会议模式长时间录音，不因按键或点击取消
+            if app_state.is_meeting.load(Ordering::SeqCst) {
+                return;
+            }
+
+            let cancel = app_state.config.lock().unwrap().cancel.clone();
+
+            // 检测到配置的按键或窗口外鼠标点击，取消录音
             match event.event_type {
-                EventType::KeyPress(key) if !is_modifier_only(&key) => {
+                EventType::KeyPress(key) if is_cancel_key(&cancel.key, &key) => {
                     log::info!("录音状态下检测到按键，取消录音");
-                    crate::utils::recording_state::set(&app_state, &app_handle, false);
+                    crate::utils::recording_state::cancel(&app_state, &app_handle);
                 }
-                EventType::ButtonPress(btn) => {
+                EventType::ButtonPress(btn) if cancel.outside_click => {
                     // 获取鼠标位置
                     if let Some((x, y)) = get_mouse_pos() {
                         // 检查是否在主窗口内
@@ -88,7 +105,7 @@ pub fn init(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
                         }
                     }
                     log::info!("录音状态下检测到窗口外鼠标点击 {:?}，取消录音", btn);
-                    crate::utils::recording_state::set(&app_state, &app_handle, false);
+                    crate::utils::recording_state::cancel(&app_state, &app_handle);
                 }
                 _ => {}
             }
@@ -33,17 +33,34 @@ impl InputSimulator {
             '\x08' => {
                 // 退格键
                 let _ = enigo.key(enigo::Key::Backspace, Direction::Click);
+                let _ = app_state.inserted_chars.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n.saturating_sub(1)));
+                thread::sleep(Duration::from_millis(20));
+            }
+            '\x01' | '\x1a' => {
+                // 全选 / 撤销：发送组合键，之后无法确定已输入的字符数
+                let key = if ch == '\x01' { 'a' } else { 'z' };
+                Self::key_combo(enigo, key);
+                app_state.inserted_chars.store(0, Ordering::SeqCst);
                 thread::sleep(Duration::from_millis(20));
             }
             ch => {
                 // 普通字符
                 let _ = enigo.text(&ch.to_string());
+                app_state.inserted_chars.fetch_add(1, Ordering::SeqCst);
                 thread::sleep(Duration::from_millis(20));
             }
         }
 
         app_state.is_simulating_input.store(false, Ordering::SeqCst);
     }
+
+    /// 发送 Ctrl+key（macOS 为 Cmd+key）
+    fn key_combo(enigo: &mut Enigo, key: char) {
+        let modifier = if cfg!(target_os = "macos") { enigo::Key::Meta } else { enigo::Key::Control };
+        let _ = enigo.key(modifier, Direction::Press);
+        let _ = enigo.key(enigo::Key::Unicode(key), Direction::Click);
+        let _ = enigo.key(modifier, Direction::Release);
+    }
 }
 
 impl Default for InputSimulator {
@@ -52,6 +69,30 @@ impl Default for InputSimulator {
     }
 }
 
+/// 撤销上一次听写：按已输入字符数发送退格
+///
+/// 仅在未录音时执行，返回删除的字符数。Windows 上还要求焦点仍在听写时的窗口；
+/// 其他平台无法获取前台窗口，不检查焦点，须由用户确认焦点未切换
+pub fn undo_last_dictation(app_state: &AppState) -> Result<usize, String> {
+    use std::sync::atomic::Ordering;
+
+    if *app_state.is_recording.lock().unwrap() {
+        return Err("录音中无法撤销".to_string());
+    }
+    match crate::utils::window_ext::foreground_window() {
+        Some(current) if *app_state.dictation_window.lock().unwrap() != Some(current) => {
+            return Err("焦点已切换到其他窗口".to_string());
+        }
+        Some(_) => {}
+        None => log::info!("当前平台无法获取前台窗口，撤销时不检查焦点"),
+    }
+
+    let count = app_state.inserted_chars.swap(0, Ordering::SeqCst);
+    app_state.text_buffer.push_backspaces(count);
+    log::info!("撤销上一次听写: {} 字符", count);
+    Ok(count)
+}
+
 pub fn init_input_simulator(app_state: Arc<AppState>) {
     Arc::new(InputSimulator::new()).start(app_state);
 }
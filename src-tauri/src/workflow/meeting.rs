@@ -0,0 +1,254 @@
+use crate::asr::manager::{self, AsrProvider};
+use crate::asr::transcript::Segment;
+use crate::models::buffer::{AudioBuffer, TextBuffer};
+use crate::models::config::AppConfig;
+use crate::models::state::AppState;
+use crate::utils::export::{self, ExportFormat, Session};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{Emitter, Manager};
+
+/// 结束连接后等待服务端返回最终结果的最长时间
+const DRAIN_GRACE: Duration = Duration::from_secs(10);
+/// 连接失败后的重试间隔
+const RETRY_INTERVAL: Duration = Duration::from_secs(3);
+/// 录音固定为 16kHz 单声道
+const SAMPLES_PER_MS: u64 = 16;
+
+/// 一次服务商连接
+struct Connection {
+    provider: AsrProvider,
+    /// 连接读取的第一个采样在录音中的时间（毫秒）
+    offset: u64,
+    /// 建立连接的时间，用于判断会话时长上限
+    connected_at: Instant,
+    segments: Arc<Mutex<Vec<Segment>>>,
+}
+
+/// 一次会议转写
+struct MeetingSession {
+    limit: Option<Duration>,
+    /// 录音缓冲区，按已读取的采样数计算句子在录音中的时间
+    audio_buffer: Arc<AudioBuffer>,
+    /// Transcript 的输出缓冲区（会议模式不模拟输入，定期清空）
+    text_buffer: Arc<TextBuffer>,
+    active: Option<Connection>,
+    last_attempt: Option<Instant>,
+    file: File,
+    path: PathBuf,
+    /// 会议开始时确定的配置（已应用方案）和前台应用，轮换连接时沿用
+    config: AppConfig,
+    app: Option<String>,
+    /// 会议开始时间（Unix 秒）和服务商，用于存档
+    started_unix: u64,
+    provider: String,
+    segments: Vec<Segment>,
+}
+
+/// 会议控制器
+///
+/// 长时间连续录音：不响应全局输入取消，不模拟键盘输入，不识别语音指令；到达服务商会话时长上限前
+/// 轮换连接（旧连接返回最终结果后再建立新连接，期间的音频留在缓冲区中），
+/// 按各连接读取的第一个采样在录音中的位置拼接各句时间戳，实时追加写入转写文件
+pub fn init(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
+        rt.block_on(run_meeting_loop(app_state, app_handle));
+    });
+
+    log::info!("会议控制器已启动");
+}
+
+async fn run_meeting_loop(app_state: Arc<AppState>, app_handle: tauri::AppHandle) {
+    let mut session: Option<MeetingSession> = None;
+
+    loop {
+        let active = app_state.is_meeting.load(Ordering::SeqCst) && *app_state.is_recording.lock().unwrap();
+
+        match (session.is_some(), active) {
+            (false, true) => match MeetingSession::start(&app_state, &app_handle) {
+                Ok(s) => {
+                    log::info!("会议转写开始: {}", s.path.display());
+                    session = Some(s);
+                }
+                Err(e) => {
+                    log::error!("会议转写启动失败: {}", e);
+                    super::asr_controller::report_start_error(&app_handle, &e);
+                    app_state.is_meeting.store(false, Ordering::SeqCst);
+                    crate::utils::recording_state::set(&app_state, &app_handle, false);
+                }
+            },
+            (true, true) => session.as_mut().unwrap().tick(&app_state).await,
+            (true, false) => {
+                let s = session.take().unwrap();
+                let path = s.finish().await;
+                app_state.is_meeting.store(false, Ordering::SeqCst);
+                log::info!("会议转写结束: {}", path.display());
+                let _ = app_handle.emit("meeting-finished", path);
+            }
+            (false, false) => {}
+        }
+
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+impl MeetingSession {
+    fn start(app_state: &AppState, app_handle: &tauri::AppHandle) -> Result<Self, String> {
+        let app = crate::utils::window_ext::foreground_app();
+        let config = app_state.config.lock().unwrap().effective(app.as_deref());
+        crate::models::validation::check_credentials(&config).map_err(|e| e.to_string())?;
+        let provider = config.asr.provider.clone();
+        if config.meeting.diarization && !manager::supports_diarization(&provider) {
+            log::warn!("{} 不支持说话人分离，忽略", provider);
+        }
+
+        let dir = if config.meeting.output_dir.trim().is_empty() {
+            app_handle
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("获取数据目录失败: {}", e))?
+                .join("meetings")
+        } else {
+            PathBuf::from(config.meeting.output_dir.trim())
+        };
+        std::fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}", e))?;
+
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let path = dir.join(format!("meeting-{}.txt", secs));
+        let file = File::create(&path).map_err(|e| format!("创建转写文件失败: {}", e))?;
+
+        Ok(Self {
+            limit: manager::session_limit(&provider),
+            audio_buffer: app_state.audio_buffer.clone(),
+            text_buffer: Arc::new(TextBuffer::new()),
+            active: None,
+            last_attempt: None,
+            file,
+            path,
+            config,
+            app,
+            started_unix: secs,
+            provider,
+            segments: Vec::new(),
+        })
+    }
+
+    /// 建立新的服务商连接
+    ///
+    /// 旧连接停止读取后到新连接建立前的音频留在缓冲区中，新连接从缓冲区当前位置开始读取，
+    /// 因此以已读取的采样数作为时间偏移（不受等待最终结果和建立连接的耗时影响）
+    async fn connect(&mut self, app_state: &AppState) -> Result<(), String> {
+        self.last_attempt = Some(Instant::now());
+        let offset = self.audio_position();
+        let segments = Arc::new(Mutex::new(Vec::new()));
+        let provider = app_state
+            .asr_manager
+            .create_meeting_provider(self.config.clone(), self.app.as_deref(), self.text_buffer.clone(), segments.clone())?;
+        provider.start().await?;
+        self.active = Some(Connection {
+            provider,
+            offset,
+            connected_at: Instant::now(),
+            segments,
+        });
+        Ok(())
+    }
+
+    async fn tick(&mut self, app_state: &AppState) {
+        self.text_buffer.clear();
+
+        // 连接断开或未建立时重试
+        if self.active.is_none() && self.last_attempt.is_none_or(|t| t.elapsed() >= RETRY_INTERVAL) {
+            if let Err(e) = self.connect(app_state).await {
+                log::error!("会议转写连接失败: {}", e);
+            }
+        }
+
+        // 到达会话时长上限前轮换连接
+        let expired = match (&self.active, self.limit) {
+            (Some(c), Some(limit)) => c.connected_at.elapsed() >= limit,
+            _ => false,
+        };
+        if expired {
+            log::info!("会议转写轮换连接");
+            let old = self.active.take().unwrap();
+            old.provider.end_audio();
+            self.close(old).await;
+            if let Err(e) = self.connect(app_state).await {
+                log::error!("会议转写连接失败: {}", e);
+            }
+        }
+
+        let segments = self.active.as_ref().map(|c| self.drain(c)).unwrap_or_default();
+        self.write(segments);
+    }
+
+    /// 等待已结束发送的连接返回最终结果，写入剩余的句子后断开
+    async fn close(&mut self, connection: Connection) {
+        if !connection.provider.wait_closed(DRAIN_GRACE).await {
+            log::warn!("会议转写连接 {:?} 内未返回最终结果", DRAIN_GRACE);
+        }
+        connection.provider.cancel().await;
+        let segments = self.drain(&connection);
+        self.write(segments);
+    }
+
+    /// 已发送给服务商的音频在录音中的位置（毫秒）
+    fn audio_position(&self) -> u64 {
+        self.audio_buffer.consumed() / SAMPLES_PER_MS
+    }
+
+    /// 取出连接新定稿的句子，换算为在录音中的时间
+    fn drain(&self, c: &Connection) -> Vec<Segment> {
+        // 服务商未提供时间时以已发送音频的位置为准
+        let fallback = self.audio_position();
+        let mut segments: Vec<Segment> = c.segments.lock().unwrap().drain(..).collect();
+        for seg in &mut segments {
+            seg.start_ms = Some(seg.start_ms.map_or(fallback, |t| c.offset + t));
+            seg.end_ms = Some(seg.end_ms.map_or(fallback, |t| c.offset + t));
+        }
+        segments
+    }
+
+    /// 按时间顺序写入转写文件
+    fn write(&mut self, mut segments: Vec<Segment>) {
+        segments.sort_by_key(|s| s.start_ms);
+        for seg in segments {
+            let line = match &seg.speaker {
+                Some(speaker) => format!("[{}] 说话人{}: {}", format_time(seg.start_ms.unwrap_or(0)), speaker, seg.text),
+                None => format!("[{}] {}", format_time(seg.start_ms.unwrap_or(0)), seg.text),
+            };
+            if let Err(e) = writeln!(self.file, "{}", line).and_then(|_| self.file.flush()) {
+                log::error!("写入转写文件失败: {}", e);
+            }
+            self.segments.push(seg);
+        }
+    }
+
+    /// 停止连接（录音已停止，发完缓冲区中剩余的音频），等待最终结果后写入文件，
+    /// 并保存 JSON 存档（可导出为字幕等格式）
+    async fn finish(mut self) -> PathBuf {
+        if let Some(c) = self.active.take() {
+            c.provider.stop().await;
+            self.close(c).await;
+        }
+        let session = Session::new(self.started_unix, self.provider, self.segments);
+        let archive = self.path.with_extension("json");
+        if let Err(e) = export::export(&session, ExportFormat::Json, &archive) {
+            log::error!("保存会议存档失败: {}", e);
+        }
+        self.path
+    }
+}
+
+/// 毫秒格式化为 hh:mm:ss
+fn format_time(ms: u64) -> String {
+    let secs = ms / 1000;
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
@@ -2,3 +2,7 @@ pub mod recorder;
 pub mod input_simulator;
 pub mod asr_controller;
 pub mod global_input;
+pub mod funasr_service;
+pub mod meeting;
+pub mod config_watcher;
+pub mod control_api;
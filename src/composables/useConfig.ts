@@ -19,6 +19,21 @@ export interface AppConfig {
   asr: ASRConfig
 }
 
+/** 后端校验错误：message 为概要，fields 为各字段的错误（字段路径 -> 说明） */
+export interface ConfigError {
+  message: string
+  fields: Record<string, string>
+}
+
+/** 把 sync_config 的失败结果统一为 ConfigError（命令可能返回字符串或校验错误对象） */
+export function toConfigError(e: unknown): ConfigError {
+  if (e && typeof e === 'object' && 'message' in e) {
+    const { message, fields } = e as Partial<ConfigError>
+    return { message: String(message), fields: fields ?? {} }
+  }
+  return { message: String(e), fields: {} }
+}
+
 const defaultConfig: AppConfig = {
   shortcut: '',
   auto_start: false,
@@ -34,6 +49,8 @@ const defaultConfig: AppConfig = {
 // 全局共享的配置状态
 const globalConfig: Ref<AppConfig> = ref<AppConfig>({ ...defaultConfig })
 const isLoaded = ref(false)
+// 最近一次保存的字段错误，保存成功后清空
+const fieldErrors = ref<Record<string, string>>({})
 
 /**
  * 配置管理 composable
@@ -54,9 +71,16 @@ export function useConfig() {
     }
   }
 
-  /** 保存到后端 */
+  /** 保存到后端，校验失败时记录字段错误并抛出 ConfigError */
   async function save() {
-    await invoke('sync_config', { newConfig: globalConfig.value })
+    try {
+      await invoke('sync_config', { newConfig: globalConfig.value })
+      fieldErrors.value = {}
+    } catch (e) {
+      const error = toConfigError(e)
+      fieldErrors.value = error.fields
+      throw error
+    }
   }
 
   /** 更新单个配置项并自动保存 */
@@ -81,5 +105,5 @@ export function useConfig() {
     load()
   }
 
-  return { config: globalConfig, loading, load, save, update }
+  return { config: globalConfig, loading, fieldErrors, load, save, update }
 }